@@ -10,6 +10,7 @@ use modhost::init_logger;
 use ron::ser::PrettyConfig;
 use search::MeilisearchService;
 use std::{fs, path::PathBuf};
+use storage::{gallery_storage, packages_storage};
 use tracing::level_filters::LevelFilter;
 
 #[tokio::main]
@@ -21,8 +22,8 @@ pub async fn main() -> Result<()> {
 
     run_migrations(&pool).await?;
 
-    let pkgs = config.storage.packages()?;
-    let imgs = config.storage.gallery()?;
+    let pkgs = packages_storage(&config.storage)?;
+    let imgs = gallery_storage(&config.storage)?;
 
     let mods_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("mods");
     let raw = fs::read_to_string(mods_dir.join("mods.json"))?;
@@ -46,7 +47,7 @@ pub async fn main() -> Result<()> {
 
     for item in dump.into_iter().progress() {
         let (pkg, _) = item
-            .upload_all(id, &mut pool.get().await?, &pkgs, &imgs)
+            .upload_all(id, &mut pool.get().await?, pkgs.as_ref(), imgs.as_ref())
             .await?;
 
         tags.extend(pkg.tags);
@@ -1,16 +1,18 @@
 use anyhow::Result;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use db::{
-    gallery_images, package_authors, package_versions, packages, DbConn, NewGalleryImage,
-    NewPackage, NewPackageVersion, Package, PackageAuthor, PackageVersion, PackageVisibility,
+    gallery_images, package_authors, package_versions, packages, version_files, AuthorRole, DbConn,
+    NewGalleryImage, NewPackage, NewPackageVersion, NewVersionFile, Package, PackageAuthor,
+    PackageVersion, PackageVisibility, VersionChannel,
 };
 use diesel::{insert_into, SelectableHelper};
 use diesel_async::RunQueryDsl;
-use s3::Bucket;
+use modhost::util::hash::FileHashes;
 use serde::{Deserialize, Serialize};
 use serde_this_or_that::{as_bool, as_i64};
 use sha1::{Digest, Sha1};
 use std::{fs, path::PathBuf};
+use storage::StorageBackend;
 
 pub const DESC_PREFIX: &str = "> *If this is your package, please contact **@RedstoneWizard08** on the [Astroneer Modding Discord](https://discord.gg/bBqdVYxu4k) to claim it!*\n\n";
 
@@ -169,32 +171,29 @@ impl From<DumpMod> for Mod {
 }
 
 impl Version {
-    pub async fn upload(&self, bucket: &Box<Bucket>) -> Result<String> {
+    pub async fn upload(&self, bucket: &dyn StorageBackend) -> Result<FileHashes> {
         let mods_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join("mods")
             .join("releaseMods");
 
         let file_path = mods_dir.join(&self.release_file_name);
         let file = fs::read(file_path)?;
-        let mut hasher = Sha1::new();
+        let hashes = FileHashes::new(&file);
 
-        hasher.update(&file);
+        bucket.put(&hashes.file_id(), &file).await?;
 
-        let file_id = format!("{:x}", hasher.finalize());
-
-        bucket.put_object(format!("/{}", &file_id), &file).await?;
-
-        Ok(file_id)
+        Ok(hashes)
     }
 
     pub async fn as_ver(
         self,
         pkg: &Package,
         db: &mut DbConn,
-        bucket: &Box<Bucket>,
+        bucket: &dyn StorageBackend,
     ) -> Result<PackageVersion> {
-        let id = self.upload(bucket).await?;
-        let ver = self.into_ver(pkg, id);
+        let hashes = self.upload(bucket).await?;
+        let file_name = self.release_file_name.clone();
+        let ver = self.into_ver(pkg, hashes.clone());
 
         let ver = insert_into(package_versions::table)
             .values(ver)
@@ -202,19 +201,38 @@ impl Version {
             .get_result(db)
             .await?;
 
+        insert_into(version_files::table)
+            .values(NewVersionFile {
+                version: ver.id,
+                file_id: hashes.file_id(),
+                file_name: Some(file_name),
+                size: Some(hashes.size),
+                sha1: hashes.sha1,
+                sha256: Some(hashes.sha256),
+                sha512: Some(hashes.sha512),
+                is_primary: true,
+            })
+            .execute(db)
+            .await?;
+
         Ok(ver)
     }
 
-    pub fn into_ver(self, pkg: &Package, file_id: String) -> NewPackageVersion {
+    pub fn into_ver(self, pkg: &Package, hashes: FileHashes) -> NewPackageVersion {
         NewPackageVersion {
             package: pkg.id,
             name: self.version.clone(),
             version_number: self.version,
-            file_id,
+            file_id: hashes.file_id(),
             changelog: Some("Migrated from astroneermods.space.".into()),
             loaders: vec![Some("AstroModIntegrator".into())],
             game_versions: vec![Some(self.astro_build)],
             downloads: 0,
+            sha1: hashes.sha1,
+            sha256: Some(hashes.sha256),
+            sha512: Some(hashes.sha512),
+            file_size: Some(hashes.size),
+            channel: VersionChannel::Release,
         }
     }
 }
@@ -236,6 +254,7 @@ impl Mod {
                 PackageVisibility::Private
             },
             license: Some(self.license),
+            organization: None,
         }
     }
 
@@ -243,8 +262,8 @@ impl Mod {
         self,
         user_id: i32,
         db: &mut DbConn,
-        bucket: &Box<Bucket>,
-        imgs: &Box<Bucket>,
+        bucket: &dyn StorageBackend,
+        imgs: &dyn StorageBackend,
     ) -> Result<(Package, Vec<PackageVersion>)> {
         let pkg = self.clone().into_pkg();
 
@@ -257,6 +276,7 @@ impl Mod {
         let author = PackageAuthor {
             user_id,
             package: pkg.id,
+            role: AuthorRole::Owner,
         };
 
         insert_into(package_authors::table)
@@ -278,7 +298,7 @@ impl Mod {
 
             let img_id = format!("{:x}", hasher.finalize());
 
-            imgs.put_object(format!("/{}", &img_id), &img).await?;
+            imgs.put(&img_id, &img).await?;
 
             let img_data = NewGalleryImage {
                 name: self.mod_id.clone(),
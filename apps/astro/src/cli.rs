@@ -3,7 +3,7 @@ use anyhow::Result;
 use clap::{Command, CommandFactory, Parser};
 use clap_complete::{generate, Generator, Shell};
 use clap_verbosity_flag::{InfoLevel, Verbosity};
-use modhost::{from_log_level, init_logger, loaders, ModHost};
+use modhost::{from_log_level, init_logger, loaders, ModHost, NoopVerifier};
 use std::io::stdout;
 
 #[derive(Debug, Clone, Parser)]
@@ -30,7 +30,7 @@ impl Cli {
         let _ = dotenvy::dotenv();
         init_logger(from_log_level(self.verbose.log_level_filter()));
 
-        ModHost::new(Box::new(|_| true))
+        ModHost::new(Box::new(NoopVerifier))
             .await?
             .versions(get_astro_versions().await?)
             .loaders(loaders!["AstroModIntegrator", "UE4SS"])
@@ -1,15 +1,23 @@
-use axum::body::Bytes;
 use clap::{Command, CommandFactory, Parser};
 use clap_complete::{generate, Generator, Shell};
 use clap_verbosity_flag::{InfoLevel, Verbosity};
 use db::PackageManifest;
 use flate2::read::GzDecoder;
-use modhost::{from_log_level, init_logger, loaders, GameVersion, ModHost, Result};
+use modhost::{
+    from_log_level, init_logger, loaders, GameVersion, ModHost, PackageVerifier, Result,
+    ValidationReport,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::error::Category;
+use std::fs::File;
 use std::io::stdout;
-use std::io::{Cursor, Read};
+use std::io::Read;
+use std::path::Path;
 use tar::Archive;
 
+/// The largest `kjspkg.json` a package can have, in bytes.
+pub const MAX_MANIFEST_SIZE: u64 = 1024 * 1024;
+
 pub const PISTON_META_ENDPOINT: &str =
     "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
 
@@ -37,7 +45,7 @@ impl Cli {
         let _ = dotenvy::dotenv();
         init_logger(from_log_level(self.verbose.log_level_filter()));
 
-        ModHost::new(Box::new(verify_package))
+        ModHost::new(Box::new(KjsPkgVerifier))
             .await?
             .versions(get_minecraft_versions().await?)
             .loaders(loaders!["Forge", "Fabric", "Quilt", "NeoForge",])
@@ -93,35 +101,86 @@ pub async fn get_minecraft_versions() -> Result<Vec<GameVersion>> {
         .collect())
 }
 
-pub fn verify_package(bytes: Bytes) -> bool {
-    let mut data = GzDecoder::new(Cursor::new(bytes));
-    let mut gunzip = Vec::new();
+/// The verifier for KJSPKG packages.
+/// Packages must be gzipped tarballs with a `kjspkg.json` manifest at their root.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KjsPkgVerifier;
 
-    if let Err(_) = data.read_to_end(&mut gunzip) {
-        return false;
-    }
+impl PackageVerifier for KjsPkgVerifier {
+    fn verify(&self, file: &Path) -> ValidationReport {
+        let report = ValidationReport::new();
+
+        let file = match File::open(file) {
+            Ok(it) => it,
+            Err(err) => return report.error(format!("Could not open package: {}", err)),
+        };
 
-    let mut archive = Archive::new(Cursor::new(gunzip));
+        // The archive is read as it's decompressed, so we never hold more
+        // than the manifest in memory, however large the package unpacks to.
+        let mut archive = Archive::new(GzDecoder::new(file));
+
+        let entries = match archive.entries() {
+            Ok(it) => it,
+            Err(err) => {
+                return report.error(format!("Package is not a valid gzipped tarball: {}", err))
+            }
+        };
 
-    if let Ok(entries) = archive.entries() {
         for entry in entries {
-            if let Ok(mut entry) = entry {
-                if entry.path().unwrap_or_default().to_str().unwrap() == "kjspkg.json" {
-                    let mut data = String::new();
+            let entry = match entry {
+                Ok(it) => it,
+                Err(err) => return report.error(format!("Could not read tarball entry: {}", err)),
+            };
 
-                    if let Err(_) = entry.read_to_string(&mut data) {
-                        return false;
-                    }
+            if entry.path().unwrap_or_default().to_str() != Some("kjspkg.json") {
+                continue;
+            }
 
-                    if let Err(_) = serde_json::from_str::<PackageManifest>(&data) {
-                        return false;
-                    }
+            if entry.size() > MAX_MANIFEST_SIZE {
+                return report.error(format!(
+                    "kjspkg.json is too large! The limit is {} bytes.",
+                    MAX_MANIFEST_SIZE
+                ));
+            }
 
-                    return true;
-                }
+            let mut data = String::new();
+
+            if let Err(err) = entry.take(MAX_MANIFEST_SIZE).read_to_string(&mut data) {
+                return report.error(format!("Could not read kjspkg.json: {}", err));
             }
+
+            return match serde_json::from_str::<PackageManifest>(&data) {
+                Ok(manifest) => check_manifest(report, manifest),
+
+                Err(err) => match err.classify() {
+                    Category::Data => {
+                        report.error(format!("Invalid field in kjspkg.json: {}", err))
+                    }
+                    _ => report.error(format!("kjspkg.json is not valid JSON: {}", err)),
+                },
+            };
         }
+
+        report.error("Missing kjspkg.json in the root of the package!")
+    }
+}
+
+fn check_manifest(mut report: ValidationReport, manifest: PackageManifest) -> ValidationReport {
+    if manifest.authors.is_empty() {
+        report = report.warn("The manifest doesn't list any authors.");
+    }
+
+    if manifest.description.trim().is_empty() {
+        report = report.warn("The manifest has an empty description.");
+    }
+
+    if manifest.loaders.is_empty() {
+        report = report.warn("The manifest doesn't list any loaders.");
+    }
+
+    if manifest.game_versions.is_empty() {
+        report = report.warn("The manifest doesn't list any game versions.");
     }
 
-    false
+    report.with_manifest(manifest)
 }
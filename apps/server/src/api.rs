@@ -7,7 +7,7 @@ use utoipa::{
 #[openapi(
     info(
         title = "ModHost API",
-        description = "The ModHost REST API.",
+        description = "The ModHost REST API.\n\nErrors are returned as an `ErrorBody`, with a stable `code` (see `ErrorCode`), a human-readable `message`, and optional `details`.",
 
         license(
             name = "MIT",
@@ -18,11 +18,30 @@ use utoipa::{
         crate::routes::api::yaml_api,
         crate::routes::api::json_api,
         crate::routes::users::me::me_handler,
+        crate::routes::users::tokens::list_handler,
+        crate::routes::users::tokens::create_handler,
+        crate::routes::users::tokens::delete_handler,
+        crate::routes::users::sessions::list_handler,
+        crate::routes::users::sessions::revoke_handler,
+        crate::routes::users::identities::list_handler,
+        crate::routes::users::identities::delete_handler,
         crate::routes::users::info::info_handler,
         crate::routes::users::pkg::list_handler,
+        crate::routes::users::orgs::list_handler,
         crate::routes::users::search::search_handler,
+        crate::routes::orgs::info::info_handler,
+        crate::routes::orgs::info::create_handler,
+        crate::routes::orgs::info::update_handler,
+        crate::routes::orgs::info::delete_handler,
+        crate::routes::orgs::members::list_handler,
+        crate::routes::orgs::members::set_handler,
+        crate::routes::orgs::members::remove_handler,
+        crate::routes::orgs::pkg::list_handler,
+        crate::routes::orgs::search::search_handler,
         crate::routes::auth::login::login_handler,
         crate::routes::auth::callback::callback_handler,
+        crate::routes::auth::logout::logout_handler,
+        crate::routes::auth::providers::providers_handler,
         crate::routes::pkg::info::info_handler,
         crate::routes::pkg::info::update_handler,
         crate::routes::pkg::info::delete_handler,
@@ -34,10 +53,16 @@ use utoipa::{
         crate::routes::pkg::ver::update_handler,
         crate::routes::pkg::ver::delete_handler,
         crate::routes::pkg::ver::latest_handler,
+        crate::routes::pkg::ver::dependencies_handler,
+        crate::routes::pkg::ver::files_handler,
+        crate::routes::pkg::ver::dependents_handler,
+        crate::routes::pkg::stats::downloads_handler,
         crate::routes::pkg::author::list_handler,
         crate::routes::pkg::author::add_handler,
         crate::routes::pkg::author::remove_handler,
+        crate::routes::pkg::author::set_role_handler,
         crate::routes::pkg::search::search_handler,
+        crate::routes::resolve::resolve_handler,
         crate::routes::pkg::gallery::list_handler,
         crate::routes::pkg::gallery::upload_handler,
         crate::routes::pkg::gallery::update_handler,
@@ -52,12 +77,24 @@ use utoipa::{
     components(
         schemas(
             db::User,
+            db::UserIdentity,
+            crate::auth::providers::ProviderInfo,
             db::UserToken,
             db::NewUser,
             db::NewUserToken,
+            db::ApiToken,
+            db::NewApiToken,
+            db::TokenScope,
+            crate::routes::users::tokens::CreateTokenRequest,
+            crate::routes::users::tokens::CreatedToken,
+            crate::routes::users::sessions::Session,
             db::PackageManifest,
             db::Package,
             db::PackageAuthor,
+            db::AuthorRole,
+            db::AuthorData,
+            crate::routes::pkg::author::AddAuthorQuery,
+            crate::routes::pkg::author::SetAuthorRoleRequest,
             db::PackageRelation,
             db::PackageVersion,
             db::PackageVersionRef,
@@ -65,8 +102,17 @@ use utoipa::{
             db::NewPackage,
             db::NewPackageVersion,
             db::RelationKind,
+            db::VersionRelation,
+            db::VersionFile,
+            crate::routes::pkg::ver::DownloadQuery,
             db::PackageData,
+            db::Organization,
+            db::NewOrganization,
+            db::OrganizationData,
+            db::OrgMember,
+            db::OrgRole,
             db::PackageVisibility,
+            db::VersionChannel,
             db::GalleryImage,
             db::NewGalleryImage,
             db::PublicGalleryImage,
@@ -77,22 +123,54 @@ use utoipa::{
             crate::routes::api::JsonQueryParams,
             crate::routes::users::search::SearchQuery,
             crate::routes::pkg::info::PartialPackage,
+            crate::routes::orgs::info::PartialOrganization,
+            crate::routes::orgs::members::SetMemberRequest,
+            crate::routes::users::orgs::UserOrganization,
             crate::routes::pkg::ver::PartialPackageVersion,
+            crate::routes::pkg::ver::UploadedVersion,
+            crate::routes::pkg::ver::LatestVersionQuery,
+            crate::routes::pkg::ver::VersionListQuery,
+            crate::routes::pkg::ver::VersionSort,
             crate::routes::pkg::search::SearchQuery,
             crate::routes::pkg::gallery::PartialGalleryImage,
             crate::routes::pkg::gallery::GalleryImageUpload,
+            crate::routes::resolve::ResolveRequest,
+            crate::util::resolve::ResolveResults,
+            crate::util::resolve::ResolvedPackage,
+            crate::util::resolve::ResolveConflict,
+            crate::verify::ValidationReport,
+            db::DailyDownloads,
+            crate::routes::pkg::stats::DownloadStatsQuery,
+            crate::util::stats::Granularity,
+            crate::util::stats::DownloadPoint,
+            crate::util::stats::DownloadSeries,
+            crate::util::stats::DownloadStats,
             crate::routes::meta::vers::GameVersion,
             crate::routes::meta::loaders::ModLoader,
             crate::routes::meta::tags::Tag,
+            app_core::ErrorBody,
+            app_core::ErrorCode,
         ),
         responses(
             db::User,
+            db::UserIdentity,
+            crate::auth::providers::ProviderInfo,
             db::UserToken,
             db::NewUser,
             db::NewUserToken,
+            db::ApiToken,
+            db::NewApiToken,
+            db::TokenScope,
+            crate::routes::users::tokens::CreateTokenRequest,
+            crate::routes::users::tokens::CreatedToken,
+            crate::routes::users::sessions::Session,
             db::PackageManifest,
             db::Package,
             db::PackageAuthor,
+            db::AuthorRole,
+            db::AuthorData,
+            crate::routes::pkg::author::AddAuthorQuery,
+            crate::routes::pkg::author::SetAuthorRoleRequest,
             db::PackageRelation,
             db::PackageVersion,
             db::PackageVersionRef,
@@ -100,8 +178,17 @@ use utoipa::{
             db::NewPackage,
             db::NewPackageVersion,
             db::RelationKind,
+            db::VersionRelation,
+            db::VersionFile,
+            crate::routes::pkg::ver::DownloadQuery,
             db::PackageData,
+            db::Organization,
+            db::NewOrganization,
+            db::OrganizationData,
+            db::OrgMember,
+            db::OrgRole,
             db::PackageVisibility,
+            db::VersionChannel,
             db::GalleryImage,
             db::NewGalleryImage,
             db::PublicGalleryImage,
@@ -112,18 +199,39 @@ use utoipa::{
             crate::routes::api::JsonQueryParams,
             crate::routes::users::search::SearchQuery,
             crate::routes::pkg::info::PartialPackage,
+            crate::routes::orgs::info::PartialOrganization,
+            crate::routes::orgs::members::SetMemberRequest,
+            crate::routes::users::orgs::UserOrganization,
             crate::routes::pkg::ver::PartialPackageVersion,
+            crate::routes::pkg::ver::UploadedVersion,
+            crate::routes::pkg::ver::LatestVersionQuery,
+            crate::routes::pkg::ver::VersionListQuery,
+            crate::routes::pkg::ver::VersionSort,
             crate::routes::pkg::search::SearchQuery,
             crate::routes::pkg::gallery::PartialGalleryImage,
             crate::routes::pkg::gallery::GalleryImageUpload,
+            crate::routes::resolve::ResolveRequest,
+            crate::util::resolve::ResolveResults,
+            crate::util::resolve::ResolvedPackage,
+            crate::util::resolve::ResolveConflict,
+            crate::verify::ValidationReport,
+            db::DailyDownloads,
+            crate::routes::pkg::stats::DownloadStatsQuery,
+            crate::util::stats::Granularity,
+            crate::util::stats::DownloadPoint,
+            crate::util::stats::DownloadSeries,
+            crate::util::stats::DownloadStats,
             crate::routes::meta::vers::GameVersion,
             crate::routes::meta::loaders::ModLoader,
             crate::routes::meta::tags::Tag,
+            app_core::ErrorBody,
+            app_core::ErrorCode,
         ),
     ),
     tags(
         (name = "Auth", description = "Authentication endpoints."),
         (name = "Users", description = "User-related endpoints."),
+        (name = "Organizations", description = "Organization-related endpoints."),
         (name = "Packages", description = "Package-related endpoints."),
         (name = "Gallery", description = "Package gallery-related endpoints."),
         (name = "Versions", description = "Package version-related endpoints."),
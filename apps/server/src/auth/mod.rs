@@ -1,34 +1,128 @@
+pub mod oauth_state;
+pub mod package;
+pub mod providers;
+
+use crate::{middleware::ip::get_client_ip, Result};
 use app_core::AppError;
 use axum::http::HeaderMap;
 use axum_extra::extract::CookieJar;
-use db::{get_user_for_token, DbConn, User};
+use db::{
+    get_api_token, get_token, is_api_token, touch_api_token, touch_token, users, ApiToken, DbConn,
+    TokenScope, User, UserToken,
+};
+use diesel::{QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+/// The token a request was made with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestToken {
+    /// A login token (a session).
+    Session(UserToken),
+
+    /// A personal API token.
+    Api(ApiToken),
+}
+
+impl RequestToken {
+    /// The ID of the user this token belongs to.
+    pub fn user_id(&self) -> i32 {
+        match self {
+            Self::Session(token) => token.user_id,
+            Self::Api(token) => token.user_id,
+        }
+    }
+}
+
+pub fn get_token_from_req(jar: &CookieJar, headers: &HeaderMap) -> Result<String> {
+    if let Some(value) = headers.get("Authorization") {
+        let val = value.to_str()?;
+
+        if val.starts_with("Bearer ") {
+            return Ok(val.trim_start_matches("Bearer ").to_string());
+        }
+    }
+
+    if let Some(value) = jar.get("auth-token") {
+        Ok(value.value().to_string())
+    } else {
+        Err(AppError::MissingToken)
+    }
+}
+
+/// Look up the token a request was made with, and record that it was used.
+pub async fn get_request_token(
+    jar: &CookieJar,
+    headers: &HeaderMap,
+    conn: &mut DbConn,
+) -> Result<RequestToken> {
+    let token = get_token_from_req(jar, headers)?;
+    let ip = get_client_ip(headers);
 
-use crate::Result;
+    if is_api_token(&token) {
+        let token = get_api_token(token, conn)
+            .await?
+            .ok_or(AppError::UnknownUser)?;
 
+        touch_api_token(token.id, ip, conn).await?;
+
+        Ok(RequestToken::Api(token))
+    } else {
+        let token = get_token(token, conn).await?.ok_or(AppError::UnknownUser)?;
+
+        touch_token(token.id, ip, conn).await?;
+
+        Ok(RequestToken::Session(token))
+    }
+}
+
+/// Get the user making a request, checking that their token can do something
+/// that needs `scope` (on `package`, if the request is about one).
+///
+/// Login tokens can do anything. Personal API tokens need the scope, can only
+/// be used for their package if they're restricted to one, and only keep the
+/// user's admin permissions if they have the admin scope.
 pub async fn get_user_from_req(
     jar: &CookieJar,
     headers: &HeaderMap,
     conn: &mut DbConn,
+    scope: TokenScope,
+    package: Option<i32>,
 ) -> Result<User> {
-    let token = if let Some(value) = headers.get("Authorization") {
-        let val = value.to_str()?;
+    let token = get_request_token(jar, headers, conn).await?;
 
-        if val.starts_with("Bearer ") {
-            value.to_str()?.trim_start_matches("Bearer ").to_string()
-        } else {
-            if let Some(value) = jar.get("auth-token") {
-                value.value().to_string()
-            } else {
-                return Err(AppError::MissingToken);
-            }
+    if let RequestToken::Api(token) = &token {
+        if !token.allows(scope, package) {
+            return Err(AppError::MissingScope(scope.as_str().into()));
         }
-    } else if let Some(value) = jar.get("auth-token") {
-        value.value().to_string()
-    } else {
-        return Err(AppError::MissingToken);
-    };
+    }
+
+    let mut user = find_user(token.user_id(), conn).await?;
+
+    if let RequestToken::Api(token) = &token {
+        user.admin = user.admin && token.allows(TokenScope::Admin, package);
+    }
+
+    Ok(user)
+}
+
+/// Get the user making a request, only accepting login tokens.
+/// This is for things a personal API token should never be able to do,
+/// like creating more tokens.
+pub async fn get_session_user_from_req(
+    jar: &CookieJar,
+    headers: &HeaderMap,
+    conn: &mut DbConn,
+) -> Result<(User, UserToken)> {
+    match get_request_token(jar, headers, conn).await? {
+        RequestToken::Session(token) => Ok((find_user(token.user_id, conn).await?, token)),
+        RequestToken::Api(_) => Err(AppError::Unauthorized),
+    }
+}
 
-    get_user_for_token(token, conn)
-        .await?
-        .ok_or(AppError::UnknownUser)
+async fn find_user(id: i32, conn: &mut DbConn) -> Result<User> {
+    Ok(users::table
+        .find(id)
+        .select(User::as_select())
+        .first(conn)
+        .await?)
 }
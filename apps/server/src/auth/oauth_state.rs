@@ -0,0 +1,192 @@
+//! Signed, expiring state for the login flow.
+//!
+//! When a login starts, the server makes a random nonce and sends it to the
+//! provider as the OAuth2 `state` parameter. The nonce is also signed (along
+//! with the provider and where to go afterwards) into a short-lived cookie.
+//! The callback only continues if the cookie is valid and its nonce matches
+//! the `state` the provider sent back, so a login can't be started in one
+//! browser and finished in another.
+
+use crate::Result;
+use anyhow::anyhow;
+use app_config::AuthConfigs;
+use app_core::AppError;
+use chrono::Utc;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use oauth2::CsrfToken;
+use random_string::{charsets::ALPHANUMERIC, generate};
+use url::Url;
+
+/// The name of the cookie the login state is stored in.
+pub const OAUTH_STATE_COOKIE: &str = "oauth-state";
+
+/// How long a login has to be finished in, in seconds.
+pub const OAUTH_STATE_EXPIRE_TIME: i64 = 600;
+
+/// The state of a login that's in progress.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthState {
+    /// The ID of the login provider being used.
+    pub provider: String,
+
+    /// The random value sent to the provider as the `state` parameter.
+    pub nonce: String,
+
+    /// The path to redirect to once logged in.
+    pub to: String,
+
+    /// When the login expires, as a UNIX timestamp.
+    pub exp: i64,
+}
+
+/// The keys used to sign and check login states.
+#[derive(Clone)]
+pub struct OAuthStateKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+impl OAuthStateKeys {
+    /// Create the keys from a secret.
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            encoding: EncodingKey::from_secret(secret.as_ref()),
+            decoding: DecodingKey::from_secret(secret.as_ref()),
+        }
+    }
+
+    /// Create the keys from the configured secret, or a random one if there isn't one.
+    pub fn from_config(config: &AuthConfigs) -> Self {
+        match &config.state_secret {
+            Some(secret) => Self::new(secret),
+
+            None => {
+                warn!("No login state secret is configured, using a random one.");
+
+                Self::new(generate(64, ALPHANUMERIC))
+            }
+        }
+    }
+
+    /// Start a login, returning its state and the signed value for the cookie.
+    pub fn issue(
+        &self,
+        provider: impl AsRef<str>,
+        to: impl AsRef<str>,
+    ) -> Result<(OAuthState, String)> {
+        let state = OAuthState {
+            provider: provider.as_ref().into(),
+            nonce: CsrfToken::new_random().secret().clone(),
+            to: to.as_ref().into(),
+            exp: Utc::now().timestamp() + OAUTH_STATE_EXPIRE_TIME,
+        };
+
+        let value = encode(&Header::default(), &state, &self.encoding)
+            .map_err(|err| anyhow!("Could not sign the login state: {}", err))?;
+
+        Ok((state, value))
+    }
+
+    /// Check a signed login state from a cookie against the provider
+    /// and the `state` parameter the provider sent back.
+    pub fn verify(
+        &self,
+        value: impl AsRef<str>,
+        provider: impl AsRef<str>,
+        nonce: impl AsRef<str>,
+    ) -> Result<OAuthState> {
+        let mut validation = Validation::default();
+
+        validation.leeway = 0;
+
+        let state = decode::<OAuthState>(value.as_ref(), &self.decoding, &validation)
+            .map_err(|_| AppError::BadRequest("The login state is invalid or expired!".into()))?
+            .claims;
+
+        if state.provider != provider.as_ref() || state.nonce != nonce.as_ref() {
+            return Err(AppError::BadRequest(
+                "The login state doesn't match!".into(),
+            ));
+        }
+
+        Ok(state)
+    }
+}
+
+/// Make sure a redirect target is on this site, returning just its path and query.
+/// Anything pointing to another origin gets `None`.
+pub fn safe_redirect(to: impl AsRef<str>, scheme: &str, host: &str) -> Option<String> {
+    let base = Url::parse(&format!("{}://{}/", scheme, host)).ok()?;
+    let url = base.join(to.as_ref()).ok()?;
+
+    if url.origin() != base.origin() {
+        return None;
+    }
+
+    match url.query() {
+        Some(query) => Some(format!("{}?{}", url.path(), query)),
+        None => Some(url.path().into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_same_origin_redirects() {
+        let check = |to| safe_redirect(to, "https", "example.com");
+
+        assert_eq!(check("/"), Some("/".into()));
+        assert_eq!(
+            check("/p/my-mod?tab=versions"),
+            Some("/p/my-mod?tab=versions".into())
+        );
+        assert_eq!(check("https://example.com/me#top"), Some("/me".into()));
+        assert_eq!(
+            safe_redirect("http://localhost:4000/a", "http", "localhost:4000"),
+            Some("/a".into())
+        );
+    }
+
+    #[test]
+    fn rejects_other_origins() {
+        let check = |to| safe_redirect(to, "https", "example.com");
+
+        assert_eq!(check("https://evil.com/"), None);
+        assert_eq!(check("//evil.com/"), None);
+        assert_eq!(check("/\\evil.com/"), None);
+        assert_eq!(check("http://example.com/"), None);
+        assert_eq!(check("https://example.com:8443/"), None);
+        assert_eq!(check("javascript:alert(1)"), None);
+    }
+
+    #[test]
+    fn verifies_states() {
+        let keys = OAuthStateKeys::new("secret");
+        let (state, value) = keys.issue("github", "/me").unwrap();
+
+        assert_eq!(keys.verify(&value, "github", &state.nonce).unwrap(), state);
+        assert!(keys.verify(&value, "github", "wrong").is_err());
+        assert!(keys.verify(&value, "discord", &state.nonce).is_err());
+        assert!(OAuthStateKeys::new("other")
+            .verify(&value, "github", &state.nonce)
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_expired_states() {
+        let keys = OAuthStateKeys::new("secret");
+
+        let state = OAuthState {
+            provider: "github".into(),
+            nonce: "nonce".into(),
+            to: "/".into(),
+            exp: Utc::now().timestamp() - 1,
+        };
+
+        let value = encode(&Header::default(), &state, &keys.encoding).unwrap();
+
+        assert!(keys.verify(value, "github", "nonce").is_err());
+    }
+}
@@ -0,0 +1,407 @@
+//! Extractors for the package a request is about.
+//!
+//! These look up the package from the `:id` path parameter and check that
+//! the user making the request can see (or change) it, so handlers don't
+//! have to repeat those checks themselves.
+
+use super::get_user_from_req;
+use crate::{state::AppState, Result};
+use app_core::AppError;
+use axum::{
+    extract::{FromRequestParts, Path},
+    http::request::Parts,
+};
+use axum_extra::extract::CookieJar;
+use db::{
+    get_full_package, get_package, get_package_access, DbConn, Package, PackageAccess, PackageData,
+    PackagePermission, PackageVisibility, TokenScope, User,
+};
+use std::{collections::HashMap, marker::PhantomData};
+
+/// The permissions a [`WritablePackage`] can require.
+pub mod perm {
+    use db::PackagePermission;
+
+    /// A permission a [`WritablePackage`](super::WritablePackage) can require.
+    pub trait RequiredPermission {
+        const PERMISSION: PackagePermission;
+    }
+
+    /// Edit the package's info.
+    pub struct Edit;
+
+    /// Upload, edit, and delete versions.
+    pub struct Upload;
+
+    /// Add, edit, and remove gallery images.
+    pub struct Gallery;
+
+    /// Add and remove authors, and change their roles.
+    pub struct ManageAuthors;
+
+    /// Delete or transfer the package, and manage its owners.
+    pub struct Manage;
+
+    impl RequiredPermission for Edit {
+        const PERMISSION: PackagePermission = PackagePermission::Edit;
+    }
+
+    impl RequiredPermission for Upload {
+        const PERMISSION: PackagePermission = PackagePermission::Upload;
+    }
+
+    impl RequiredPermission for Gallery {
+        const PERMISSION: PackagePermission = PackagePermission::Gallery;
+    }
+
+    impl RequiredPermission for ManageAuthors {
+        const PERMISSION: PackagePermission = PackagePermission::ManageAuthors;
+    }
+
+    impl RequiredPermission for Manage {
+        const PERMISSION: PackagePermission = PackagePermission::Manage;
+    }
+}
+
+/// A package the user making a request can see, along with that user if
+/// they're signed in.
+/// Public and unlisted packages can be seen by anyone, but private ones
+/// need the user to have access to them. Anyone else gets a 404, as if
+/// the package didn't exist.
+#[derive(Debug, Clone)]
+pub struct ReadablePackage {
+    /// The package.
+    pub pkg: PackageData,
+
+    /// The user making the request, if they're signed in with a token that
+    /// can read this package.
+    pub user: Option<User>,
+}
+
+/// A package the user making a request can change in the way `P` says,
+/// along with that user.
+/// Private packages the user can't see get a 404, just like with
+/// [`ReadablePackage`].
+#[derive(Debug, Clone)]
+pub struct WritablePackage<P: perm::RequiredPermission = perm::Edit> {
+    /// The package.
+    pub pkg: Package,
+
+    /// The user making the request.
+    pub user: User,
+
+    _perm: PhantomData<P>,
+}
+
+/// Get the package ID or slug from a request's path.
+async fn package_id(parts: &mut Parts, state: &AppState) -> Result<String> {
+    let Path(mut params) = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+        .await
+        .map_err(|_| AppError::NotFound)?;
+
+    params.remove("id").ok_or(AppError::NotFound)
+}
+
+/// Get what the user making a request (if any) can do to a package.
+async fn get_access(
+    user: Option<&User>,
+    pkg: i32,
+    org: Option<i32>,
+    conn: &mut DbConn,
+) -> Result<PackageAccess> {
+    match user {
+        Some(user) => get_package_access(user, pkg, org, conn).await,
+        None => Ok(PackageAccess::default()),
+    }
+}
+
+/// Check that a package with the given visibility can be read by someone
+/// with the given access.
+fn check_read(visibility: PackageVisibility, access: &PackageAccess) -> Result<()> {
+    match visibility {
+        PackageVisibility::Public | PackageVisibility::Unlisted => Ok(()),
+        PackageVisibility::Private if access.allows(PackagePermission::View) => Ok(()),
+        PackageVisibility::Private => Err(AppError::NotFound),
+    }
+}
+
+/// Check that the user making a request can change a package in the way `P`
+/// says. `user` is the result of looking them up, so requests without a
+/// (good enough) token get the reason why, unless the package is private.
+fn check_write<P: perm::RequiredPermission>(
+    visibility: PackageVisibility,
+    user: Result<User>,
+    access: &PackageAccess,
+) -> Result<User> {
+    check_read(visibility, access)?;
+
+    let user = user?;
+
+    if !access.allows(P::PERMISSION) {
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(user)
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for ReadablePackage {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self> {
+        let id = package_id(parts, state).await?;
+        let jar = CookieJar::from_headers(&parts.headers);
+        let mut conn = state.pool.get().await?;
+        let pkg = get_full_package(id, &mut conn).await?;
+
+        let user = get_user_from_req(
+            &jar,
+            &parts.headers,
+            &mut conn,
+            TokenScope::Read,
+            Some(pkg.id),
+        )
+        .await
+        .ok();
+
+        // Roles only matter for private packages, so don't look them up otherwise.
+        let access = match pkg.visibility {
+            PackageVisibility::Private => {
+                get_access(user.as_ref(), pkg.id, pkg.organization, &mut conn).await?
+            }
+
+            _ => PackageAccess::default(),
+        };
+
+        check_read(pkg.visibility, &access)?;
+
+        Ok(Self { pkg, user })
+    }
+}
+
+#[async_trait]
+impl<P: perm::RequiredPermission> FromRequestParts<AppState> for WritablePackage<P> {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self> {
+        let id = package_id(parts, state).await?;
+        let jar = CookieJar::from_headers(&parts.headers);
+        let mut conn = state.pool.get().await?;
+        let pkg = get_package(id, &mut conn).await?;
+
+        let user = get_user_from_req(
+            &jar,
+            &parts.headers,
+            &mut conn,
+            P::PERMISSION.scope(),
+            Some(pkg.id),
+        )
+        .await;
+
+        let access = get_access(user.as_ref().ok(), pkg.id, pkg.organization, &mut conn).await?;
+        let user = check_write::<P>(pkg.visibility, user, &access)?;
+
+        Ok(Self {
+            pkg,
+            user,
+            _perm: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::{AuthorRole, OrgRole};
+    use perm::RequiredPermission;
+
+    const VISIBILITIES: [PackageVisibility; 3] = [
+        PackageVisibility::Public,
+        PackageVisibility::Unlisted,
+        PackageVisibility::Private,
+    ];
+
+    /// Someone making a request, and what they can do to the package.
+    struct Caller {
+        user: Option<User>,
+        access: PackageAccess,
+    }
+
+    impl Caller {
+        fn anonymous() -> Self {
+            Self {
+                user: None,
+                access: PackageAccess::default(),
+            }
+        }
+
+        fn user(admin: bool, access: PackageAccess) -> Self {
+            Self {
+                user: Some(User {
+                    id: 1,
+                    username: "someone".into(),
+                    github_id: -1,
+                    admin,
+                }),
+                access,
+            }
+        }
+
+        fn stranger() -> Self {
+            Self::user(false, PackageAccess::default())
+        }
+
+        fn author(role: AuthorRole) -> Self {
+            Self::user(
+                false,
+                PackageAccess {
+                    author: Some(role),
+                    ..Default::default()
+                },
+            )
+        }
+
+        fn org_member(role: OrgRole) -> Self {
+            Self::user(
+                false,
+                PackageAccess {
+                    org: Some(role),
+                    ..Default::default()
+                },
+            )
+        }
+
+        fn admin() -> Self {
+            Self::user(
+                true,
+                PackageAccess {
+                    admin: true,
+                    ..Default::default()
+                },
+            )
+        }
+
+        fn read(&self, visibility: PackageVisibility) -> Result<()> {
+            check_read(visibility, &self.access)
+        }
+
+        fn write<P: RequiredPermission>(&self, visibility: PackageVisibility) -> Result<User> {
+            let user = self.user.clone().ok_or(AppError::MissingToken);
+
+            check_write::<P>(visibility, user, &self.access)
+        }
+    }
+
+    #[test]
+    fn anyone_can_read_public_and_unlisted_packages() {
+        for visibility in [PackageVisibility::Public, PackageVisibility::Unlisted] {
+            for caller in [
+                Caller::anonymous(),
+                Caller::stranger(),
+                Caller::author(AuthorRole::GalleryEditor),
+                Caller::org_member(OrgRole::Member),
+                Caller::admin(),
+            ] {
+                assert!(caller.read(visibility).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn private_packages_are_hidden_from_outsiders() {
+        for caller in [Caller::anonymous(), Caller::stranger()] {
+            assert!(matches!(
+                caller.read(PackageVisibility::Private),
+                Err(AppError::NotFound)
+            ));
+
+            assert!(matches!(
+                caller.write::<perm::Edit>(PackageVisibility::Private),
+                Err(AppError::NotFound)
+            ));
+        }
+    }
+
+    #[test]
+    fn private_packages_can_be_read_with_access() {
+        for caller in [
+            Caller::author(AuthorRole::GalleryEditor),
+            Caller::author(AuthorRole::Owner),
+            Caller::org_member(OrgRole::Member),
+            Caller::admin(),
+        ] {
+            assert!(caller.read(PackageVisibility::Private).is_ok());
+        }
+    }
+
+    #[test]
+    fn anonymous_users_cant_write() {
+        for visibility in [PackageVisibility::Public, PackageVisibility::Unlisted] {
+            assert!(matches!(
+                Caller::anonymous().write::<perm::Upload>(visibility),
+                Err(AppError::MissingToken)
+            ));
+        }
+    }
+
+    #[test]
+    fn strangers_cant_write() {
+        for visibility in [PackageVisibility::Public, PackageVisibility::Unlisted] {
+            assert!(matches!(
+                Caller::stranger().write::<perm::Gallery>(visibility),
+                Err(AppError::Unauthorized)
+            ));
+        }
+    }
+
+    #[test]
+    fn authors_can_write_what_their_role_allows() {
+        for visibility in VISIBILITIES {
+            let uploader = Caller::author(AuthorRole::Uploader);
+
+            assert_eq!(uploader.write::<perm::Upload>(visibility).unwrap().id, 1);
+            assert!(uploader.write::<perm::Gallery>(visibility).is_err());
+            assert!(uploader.write::<perm::Edit>(visibility).is_err());
+
+            let maintainer = Caller::author(AuthorRole::Maintainer);
+
+            assert!(maintainer.write::<perm::Edit>(visibility).is_ok());
+            assert!(maintainer.write::<perm::ManageAuthors>(visibility).is_ok());
+            assert!(maintainer.write::<perm::Manage>(visibility).is_err());
+
+            assert!(Caller::author(AuthorRole::Owner)
+                .write::<perm::Manage>(visibility)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn org_members_can_write_but_not_manage() {
+        for visibility in VISIBILITIES {
+            let member = Caller::org_member(OrgRole::Member);
+
+            assert!(member.write::<perm::Edit>(visibility).is_ok());
+            assert!(member.write::<perm::Upload>(visibility).is_ok());
+
+            assert!(matches!(
+                member.write::<perm::ManageAuthors>(visibility),
+                Err(AppError::Unauthorized)
+            ));
+
+            assert!(Caller::org_member(OrgRole::Admin)
+                .write::<perm::Manage>(visibility)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn admins_can_do_anything() {
+        for visibility in VISIBILITIES {
+            let admin = Caller::admin();
+
+            assert!(admin.read(visibility).is_ok());
+            assert!(admin.write::<perm::Edit>(visibility).is_ok());
+            assert!(admin.write::<perm::Manage>(visibility).is_ok());
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use super::{fetch_user_json, AuthProvider, ProviderUser};
+use crate::Result;
+use app_config::{oauth_client, AuthConfig};
+use oauth2::basic::BasicClient;
+
+/// Log in with Discord.
+pub struct DiscordProvider {
+    client: BasicClient,
+}
+
+#[derive(Deserialize)]
+struct DiscordUser {
+    id: String,
+    username: String,
+}
+
+impl DiscordProvider {
+    pub fn new(config: &AuthConfig) -> Result<Self> {
+        Ok(Self {
+            client: oauth_client(
+                &config.client_id,
+                &config.client_secret,
+                "https://discord.com/oauth2/authorize",
+                "https://discord.com/api/oauth2/token",
+            )?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthProvider for DiscordProvider {
+    fn id(&self) -> &str {
+        "discord"
+    }
+
+    fn name(&self) -> &str {
+        "Discord"
+    }
+
+    fn client(&self) -> &BasicClient {
+        &self.client
+    }
+
+    fn scopes(&self) -> Vec<String> {
+        vec!["identify".into()]
+    }
+
+    async fn get_user(&self, access_token: &str) -> Result<ProviderUser> {
+        let me: DiscordUser =
+            fetch_user_json("https://discord.com/api/users/@me", access_token).await?;
+
+        Ok(ProviderUser {
+            id: me.id,
+            username: me.username,
+            github_id: None,
+        })
+    }
+}
@@ -0,0 +1,55 @@
+use super::{AuthProvider, ProviderUser};
+use crate::{util::create_github_client, Result};
+use app_config::{oauth_client, AuthConfig};
+use oauth2::basic::BasicClient;
+
+/// Log in with GitHub.
+pub struct GitHubProvider {
+    client: BasicClient,
+}
+
+impl GitHubProvider {
+    pub fn new(config: &AuthConfig) -> Result<Self> {
+        Ok(Self {
+            client: oauth_client(
+                &config.client_id,
+                &config.client_secret,
+                "https://github.com/login/oauth/authorize",
+                "https://github.com/login/oauth/access_token",
+            )?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthProvider for GitHubProvider {
+    fn id(&self) -> &str {
+        "github"
+    }
+
+    fn name(&self) -> &str {
+        "GitHub"
+    }
+
+    fn client(&self) -> &BasicClient {
+        &self.client
+    }
+
+    fn scopes(&self) -> Vec<String> {
+        vec![
+            "user:email".into(),
+            "read:user".into(),
+            "public_repo".into(),
+        ]
+    }
+
+    async fn get_user(&self, access_token: &str) -> Result<ProviderUser> {
+        let me = create_github_client(access_token)?.current().user().await?;
+
+        Ok(ProviderUser {
+            id: me.id.0.to_string(),
+            username: me.login,
+            github_id: Some(me.id.0 as i32),
+        })
+    }
+}
@@ -0,0 +1,62 @@
+use super::{fetch_user_json, AuthProvider, ProviderUser};
+use crate::Result;
+use app_config::{oauth_client, GitLabAuthConfig};
+use oauth2::basic::BasicClient;
+
+/// Log in with GitLab (gitlab.com or a self-hosted instance).
+pub struct GitLabProvider {
+    client: BasicClient,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabUser {
+    id: i64,
+    username: String,
+}
+
+impl GitLabProvider {
+    pub fn new(config: &GitLabAuthConfig) -> Result<Self> {
+        let url = config.url.trim_end_matches('/').to_string();
+
+        Ok(Self {
+            client: oauth_client(
+                &config.client_id,
+                &config.client_secret,
+                format!("{}/oauth/authorize", url),
+                format!("{}/oauth/token", url),
+            )?,
+            url,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthProvider for GitLabProvider {
+    fn id(&self) -> &str {
+        "gitlab"
+    }
+
+    fn name(&self) -> &str {
+        "GitLab"
+    }
+
+    fn client(&self) -> &BasicClient {
+        &self.client
+    }
+
+    fn scopes(&self) -> Vec<String> {
+        vec!["read_user".into()]
+    }
+
+    async fn get_user(&self, access_token: &str) -> Result<ProviderUser> {
+        let me: GitLabUser =
+            fetch_user_json(format!("{}/api/v4/user", self.url), access_token).await?;
+
+        Ok(ProviderUser {
+            id: me.id.to_string(),
+            username: me.username,
+            github_id: None,
+        })
+    }
+}
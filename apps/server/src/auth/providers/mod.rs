@@ -0,0 +1,145 @@
+//! Login providers.
+//!
+//! Every provider is an OAuth2 client that can turn an access token into the
+//! account that logged in. Accounts are linked to users through `user_identities`.
+
+mod discord;
+mod github;
+mod gitlab;
+mod oidc;
+
+pub use discord::*;
+pub use github::*;
+pub use gitlab::*;
+pub use oidc::*;
+
+use crate::Result;
+use anyhow::anyhow;
+use app_config::AuthConfigs;
+use app_core::AppError;
+use oauth2::basic::BasicClient;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+/// An account on a login provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderUser {
+    /// The account's ID on the provider.
+    pub id: String,
+
+    /// The account's username on the provider.
+    pub username: String,
+
+    /// The account's GitHub ID, if this is a GitHub account.
+    pub github_id: Option<i32>,
+}
+
+/// A login provider.
+#[async_trait::async_trait]
+pub trait AuthProvider: Send + Sync {
+    /// The provider's ID, used in login URLs and stored with identities.
+    fn id(&self) -> &str;
+
+    /// The provider's display name.
+    fn name(&self) -> &str;
+
+    /// The provider's OAuth2 client.
+    fn client(&self) -> &BasicClient;
+
+    /// The scopes to request when logging in.
+    fn scopes(&self) -> Vec<String>;
+
+    /// Get the account an access token belongs to.
+    async fn get_user(&self, access_token: &str) -> Result<ProviderUser>;
+}
+
+/// Public information about a login provider.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct ProviderInfo {
+    /// The provider's ID.
+    pub id: String,
+
+    /// The provider's display name.
+    pub name: String,
+}
+
+/// All of the configured login providers.
+#[derive(Clone, Default)]
+pub struct AuthProviders {
+    providers: Vec<Arc<dyn AuthProvider>>,
+}
+
+impl AuthProviders {
+    /// Create every provider that's configured.
+    pub fn from_config(config: &AuthConfigs) -> Result<Self> {
+        let mut providers = Self::default();
+
+        if let Some(github) = &config.github {
+            providers.add(GitHubProvider::new(github)?)?;
+        }
+
+        if let Some(gitlab) = &config.gitlab {
+            providers.add(GitLabProvider::new(gitlab)?)?;
+        }
+
+        if let Some(discord) = &config.discord {
+            providers.add(DiscordProvider::new(discord)?)?;
+        }
+
+        for oidc in &config.oidc {
+            providers.add(OidcProvider::new(oidc)?)?;
+        }
+
+        Ok(providers)
+    }
+
+    /// Add a provider.
+    pub fn add(&mut self, provider: impl AuthProvider + 'static) -> Result<()> {
+        if self.providers.iter().any(|it| it.id() == provider.id()) {
+            return Err(anyhow!("Duplicate login provider ID: {}", provider.id()).into());
+        }
+
+        self.providers.push(Arc::new(provider));
+
+        Ok(())
+    }
+
+    /// Get a provider by its ID.
+    pub fn get(&self, id: impl AsRef<str>) -> Result<Arc<dyn AuthProvider>> {
+        self.providers
+            .iter()
+            .find(|it| it.id() == id.as_ref())
+            .cloned()
+            .ok_or(AppError::NotFound)
+    }
+
+    /// List the providers.
+    pub fn list(&self) -> Vec<ProviderInfo> {
+        self.providers
+            .iter()
+            .map(|it| ProviderInfo {
+                id: it.id().into(),
+                name: it.name().into(),
+            })
+            .collect()
+    }
+}
+
+/// Fetch JSON from a provider's API with an access token.
+pub(crate) async fn fetch_user_json<T: DeserializeOwned>(
+    url: impl AsRef<str>,
+    access_token: &str,
+) -> Result<T> {
+    Ok(reqwest::Client::new()
+        .get(url.as_ref())
+        .bearer_auth(access_token)
+        .header("User-Agent", "ModHost")
+        .header("Accept", "application/json")
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
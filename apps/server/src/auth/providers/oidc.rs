@@ -0,0 +1,72 @@
+use super::{fetch_user_json, AuthProvider, ProviderUser};
+use crate::Result;
+use app_config::{oauth_client, OidcAuthConfig};
+use oauth2::basic::BasicClient;
+
+/// Log in with a generic OpenID Connect provider.
+pub struct OidcProvider {
+    client: BasicClient,
+    config: OidcAuthConfig,
+}
+
+/// The standard claims we care about from a userinfo response.
+#[derive(Deserialize)]
+struct OidcUser {
+    sub: String,
+    preferred_username: Option<String>,
+    nickname: Option<String>,
+    name: Option<String>,
+    email: Option<String>,
+}
+
+impl OidcProvider {
+    pub fn new(config: &OidcAuthConfig) -> Result<Self> {
+        Ok(Self {
+            client: oauth_client(
+                &config.client_id,
+                &config.client_secret,
+                &config.auth_url,
+                &config.token_url,
+            )?,
+            config: config.clone(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthProvider for OidcProvider {
+    fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn client(&self) -> &BasicClient {
+        &self.client
+    }
+
+    fn scopes(&self) -> Vec<String> {
+        self.config.scopes.clone()
+    }
+
+    async fn get_user(&self, access_token: &str) -> Result<ProviderUser> {
+        let me: OidcUser = fetch_user_json(&self.config.userinfo_url, access_token).await?;
+
+        let username = me
+            .preferred_username
+            .or(me.nickname)
+            .or(me.name)
+            .or(me
+                .email
+                .and_then(|it| it.split('@').next().map(|it| it.to_string())))
+            .unwrap_or_else(|| me.sub.clone());
+
+        Ok(ProviderUser {
+            id: me.sub,
+            username,
+            github_id: None,
+        })
+    }
+}
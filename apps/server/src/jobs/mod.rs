@@ -0,0 +1,121 @@
+//! A small scheduler for periodic background jobs.
+//!
+//! Each job runs in its own task, at the interval it asks for (or the one set in
+//! the `jobs.intervals` config). A job that fails or panics is logged and tried
+//! again on its next tick, without affecting any other job.
+
+mod search;
+mod tokens;
+
+pub use search::*;
+pub use tokens::*;
+
+use crate::{state::AppState, Result};
+use jsglue::abort::ABORT_HANDLES;
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    task::JoinHandle,
+    time::{interval, Instant, MissedTickBehavior},
+};
+
+/// A task that runs periodically on the [`Scheduler`].
+#[async_trait::async_trait]
+pub trait Job: Send + Sync {
+    /// The job's name. This is used in logs and in the `jobs` config.
+    fn name(&self) -> &'static str;
+
+    /// How often the job runs, unless the config says otherwise.
+    fn interval(&self) -> Duration;
+
+    /// Run the job once.
+    async fn run(&self, state: &AppState) -> Result<()>;
+}
+
+/// Runs registered [`Job`]s in the background.
+pub struct Scheduler {
+    state: AppState,
+    jobs: Vec<Arc<dyn Job>>,
+}
+
+impl Scheduler {
+    /// Create a scheduler with no jobs.
+    pub fn new(state: AppState) -> Self {
+        Self {
+            state,
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Create a scheduler with all of ModHost's built-in jobs.
+    pub fn with_default_jobs(state: AppState) -> Self {
+        Self::new(state)
+            .register(CleanTokensJob)
+            .register(SearchSyncJob)
+    }
+
+    /// Register a job.
+    pub fn register(mut self, job: impl Job + 'static) -> Self {
+        self.jobs.push(Arc::new(job));
+        self
+    }
+
+    /// Start every registered job that isn't disabled in the config.
+    pub fn start(self) -> Vec<JoinHandle<()>> {
+        let config = &self.state.config.jobs;
+        let mut handles = Vec::new();
+
+        for job in self.jobs {
+            let name = job.name();
+
+            if config.disabled.iter().any(|it| it == name) {
+                info!("Job {} is disabled, skipping it.", name);
+                continue;
+            }
+
+            let every = config
+                .intervals
+                .get(name)
+                .map(|secs| Duration::from_secs(*secs))
+                .unwrap_or_else(|| job.interval());
+
+            if every.is_zero() {
+                warn!("Job {} has an interval of zero, skipping it.", name);
+                continue;
+            }
+
+            info!("Scheduling job {} (every {:?})...", name, every);
+
+            let handle = tokio::spawn(run_job(job, self.state.clone(), every));
+
+            // Hook into Glue's exit handler.
+            ABORT_HANDLES.lock().unwrap().push(handle.abort_handle());
+
+            handles.push(handle);
+        }
+
+        handles
+    }
+}
+
+async fn run_job(job: Arc<dyn Job>, state: AppState, every: Duration) {
+    let name = job.name();
+    let mut interval = interval(every);
+
+    // Don't try to catch up if a run took longer than the interval.
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        let start = Instant::now();
+        let job = job.clone();
+        let state = state.clone();
+
+        // Each run gets its own task, so a panic only fails this run.
+        match tokio::spawn(async move { job.run(&state).await }).await {
+            Ok(Ok(())) => debug!("Job {} finished in {:?}.", name, start.elapsed()),
+            Ok(Err(err)) => warn!("Job {} failed: {}", name, err),
+            Err(err) => error!("Job {} panicked: {}", name, err),
+        }
+    }
+}
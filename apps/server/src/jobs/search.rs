@@ -0,0 +1,51 @@
+use super::Job;
+use crate::{state::AppState, Result};
+use std::time::Duration;
+
+/// Updates the search index for packages whose download counts changed,
+/// instead of doing it on every single download.
+pub struct SearchSyncJob;
+
+#[async_trait::async_trait]
+impl Job for SearchSyncJob {
+    fn name(&self) -> &'static str {
+        "search_sync"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    async fn run(&self, state: &AppState) -> Result<()> {
+        let dirty = state.downloads.take_dirty();
+
+        if dirty.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = match state.pool.get().await {
+            Ok(conn) => conn,
+
+            Err(err) => {
+                // Try these again next time.
+                for pkg in dirty {
+                    state.downloads.mark_dirty(pkg);
+                }
+
+                return Err(err.into());
+            }
+        };
+
+        for pkg in dirty {
+            if let Err(err) = state.search.update_package(pkg, &mut conn).await {
+                warn!(
+                    "Could not update package {} in the search index: {}",
+                    pkg, err
+                );
+                state.downloads.mark_dirty(pkg);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use super::Job;
+use crate::{state::AppState, Result};
+use db::delete_expired_tokens;
+use std::time::Duration;
+
+/// Deletes expired login tokens and personal API tokens.
+/// Expired tokens are already rejected when they're used, so this just keeps
+/// the tables from growing forever.
+pub struct CleanTokensJob;
+
+#[async_trait::async_trait]
+impl Job for CleanTokensJob {
+    fn name(&self) -> &'static str {
+        "clean_tokens"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    async fn run(&self, state: &AppState) -> Result<()> {
+        let deleted = delete_expired_tokens(&mut state.pool.get().await?).await?;
+
+        if deleted > 0 {
+            info!("Deleted {} expired token(s).", deleted);
+        }
+
+        Ok(())
+    }
+}
@@ -18,6 +18,7 @@ pub mod auth;
 pub mod bun;
 pub mod env;
 pub mod glue;
+pub mod jobs;
 pub mod logger;
 pub mod macros;
 pub mod middleware;
@@ -26,12 +27,13 @@ pub mod server;
 pub mod state;
 pub mod ui;
 pub mod util;
-pub mod worker;
+pub mod verify;
 
 pub use logger::*;
 pub use routes::meta::loaders::ModLoader;
 pub use routes::meta::tags::Tag;
 pub use routes::meta::vers::GameVersion;
 pub use server::*;
+pub use verify::*;
 
 pub type Result<T, E = app_core::AppError> = app_core::Result<T, E>;
@@ -0,0 +1,43 @@
+use crate::{state::AppState, util::tracker::client_ip};
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::net::SocketAddr;
+
+/// The header the client's IP address is put in for the rest of the request.
+pub const CLIENT_IP_HEADER: &str = "x-modhost-client-ip";
+
+/// Figure out the IP address a request came from (see [`client_ip`]),
+/// and put it in [`CLIENT_IP_HEADER`].
+pub async fn client_ip_middleware(
+    State(state): State<AppState>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    let ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|it| client_ip(req.headers(), it.0, state.config.server.behind_proxy));
+
+    let headers = req.headers_mut();
+
+    headers.remove(CLIENT_IP_HEADER);
+
+    if let Some(value) = ip.and_then(|it| HeaderValue::from_str(&it.to_string()).ok()) {
+        headers.insert(CLIENT_IP_HEADER, value);
+    }
+
+    next.run(req).await
+}
+
+/// Get the client's IP address found by [`client_ip_middleware`].
+pub fn get_client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(CLIENT_IP_HEADER)
+        .and_then(|it| it.to_str().ok())
+        .map(|it| it.to_string())
+}
@@ -1 +1,2 @@
+pub mod ip;
 pub mod logger;
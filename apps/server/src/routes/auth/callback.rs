@@ -1,136 +1,272 @@
 use std::collections::HashMap;
 
 use crate::{
-    routes::auth::CALLBACK_URL,
+    auth::{
+        get_request_token, oauth_state::OAUTH_STATE_COOKIE, providers::ProviderUser, RequestToken,
+    },
+    routes::auth::callback_url,
     state::AppState,
-    util::{create_github_client, scheme::Scheme},
+    util::scheme::Scheme,
     Result,
 };
+use app_core::AppError;
 use axum::{
     body::Body,
-    extract::{Host, State},
+    extract::{Host, Path, State},
     http::{
         header::{LOCATION, SET_COOKIE},
-        HeaderValue, StatusCode, Uri,
+        HeaderMap, HeaderValue, StatusCode, Uri,
     },
     response::Response,
 };
-use db::{create_token, users, NewUser, User};
+use axum_extra::extract::CookieJar;
+use db::{
+    create_token, get_identity, unique_username, user_identities, users, DbConn, NewUser,
+    NewUserIdentity, User,
+};
 use diesel::{
-    dsl::insert_into, update, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper,
+    dsl::insert_into, result::DatabaseErrorKind, update, ExpressionMethods, QueryDsl,
+    SelectableHelper,
 };
-use diesel_async::RunQueryDsl;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
 use oauth2::{RedirectUrl, TokenResponse};
 
-/// GitHub Auth Callback
+/// Auth Callback
 ///
-/// Complete the GitHub login flow.
+/// Complete the login flow for a login provider.
+/// If the request is made while logged in, the provider account gets linked
+/// to the current user instead of logging in as a new one.
 #[utoipa::path(
     get,
-    path = "/api/v1/auth/github/callback",
+    path = "/api/v1/auth/{provider}/callback",
     tag = "Auth",
     responses(
-        (status = 307, description = "Success, redirecting to user info."),
+        (status = 307, description = "Success, redirecting back to where the login started."),
+        (status = 400, description = "The login failed, or its state was missing or invalid!"),
+        (status = 404, description = "That login provider isn't configured!"),
     ),
     params(
-        ("code" = String, Query, description = "Response code from GitHub"),
-        ("state" = String, Query, description = "Response state from GitHub"),
+        ("provider" = String, Path, description = "The login provider's ID (like `github`)"),
+        ("code" = String, Query, description = "Response code from the provider"),
+        ("state" = String, Query, description = "Response state from the provider"),
     ),
 )]
 pub async fn callback_handler(
     State(state): State<AppState>,
     Host(host): Host,
     Scheme(scheme): Scheme,
+    Path(provider): Path<String>,
+    jar: CookieJar,
+    headers: HeaderMap,
     url: Uri,
 ) -> Result<Response> {
+    let provider = state.auth.get(provider)?;
     let mut conn = state.pool.get().await?;
-    let query = url::form_urlencoded::parse(url.query().unwrap().as_bytes())
+    let query = url::form_urlencoded::parse(url.query().unwrap_or_default().as_bytes())
         .into_owned()
         .collect::<HashMap<String, String>>();
 
-    let code = query.get("code").unwrap();
-    let to = query.get("to");
+    if let Some(error) = query.get("error") {
+        return Err(AppError::BadRequest(format!(
+            "Couldn't log in with {}: {}",
+            provider.name(),
+            error
+        )));
+    }
+
+    let code = query
+        .get("code")
+        .ok_or(AppError::BadRequest("Missing the 'code' parameter!".into()))?;
+
+    let nonce = query.get("state").ok_or(AppError::BadRequest(
+        "Missing the 'state' parameter!".into(),
+    ))?;
+
+    let login = jar.get(OAUTH_STATE_COOKIE).ok_or(AppError::BadRequest(
+        "This login wasn't started here, or took too long! Try logging in again.".into(),
+    ))?;
 
-    let auth_url = format!("{}://{}{}", scheme, host, CALLBACK_URL);
+    let login = state
+        .oauth_state
+        .verify(login.value(), provider.id(), nonce)?;
 
-    let client = state.auth.set_redirect_uri(RedirectUrl::new(auth_url)?);
+    let auth_url = format!("{}://{}{}", scheme, host, callback_url(provider.id()));
 
-    match client
+    let client = provider
+        .client()
+        .clone()
+        .set_redirect_uri(RedirectUrl::new(auth_url)?);
+
+    let token = client
         .exchange_code(oauth2::AuthorizationCode::new(code.to_owned()))
         .request_async(oauth2::reqwest::async_http_client)
         .await
-    {
-        Ok(token) => {
-            let github_token = token.access_token().secret();
-            let client = create_github_client(github_token)?;
-            let me = client.current().user().await?;
-
-            let existing = users::table
-                .filter(users::github_id.eq(me.id.0 as i32))
-                .select(User::as_select())
-                .first(&mut conn)
-                .await
-                .optional()?;
+        .map_err(|_| AppError::BadRequest(format!("Couldn't log in with {}!", provider.name())))?;
+
+    let account = provider.get_user(token.access_token().secret()).await?;
+
+    let current = match get_request_token(&jar, &headers, &mut conn).await {
+        Ok(RequestToken::Session(token)) => Some(token.user_id),
+        _ => None,
+    };
+
+    let user = login_user(provider.id(), account, current, &mut conn).await?;
+
+    let (_, token) = create_token(user.id, &state.pool).await?;
+
+    let cookie_value = format!(
+        "auth-token={}; HttpOnly; Path=/; Domain={}",
+        token,
+        sanitize_port(&host)
+    );
+
+    let state_cookie_value = format!(
+        "{}=; HttpOnly; Path=/api/v1/auth; Max-Age=0; SameSite=Lax",
+        OAUTH_STATE_COOKIE
+    );
+
+    let separator = if login.to.contains('?') { '&' } else { '?' };
+
+    let mut response = Response::builder()
+        .status(StatusCode::TEMPORARY_REDIRECT)
+        .body(Body::new(token.clone()))?;
+
+    let headers = response.headers_mut();
+
+    headers.append(SET_COOKIE, HeaderValue::from_str(&cookie_value)?);
+    headers.append(SET_COOKIE, HeaderValue::from_str(&state_cookie_value)?);
+
+    headers.insert(
+        LOCATION,
+        HeaderValue::from_str(&format!("{}{}token={}", login.to, separator, token))?,
+    );
+
+    Ok(response)
+}
 
-            let user = if let Some(existing) = existing {
+/// How many times logging in is tried before giving up, when it keeps racing
+/// with other logins for the same username or account.
+const LOGIN_ATTEMPTS: usize = 3;
+
+/// Find (or create) the user a provider account belongs to, in a transaction.
+/// If another login takes the same username or links the same account first,
+/// this is retried, since it'll see the other login's changes the next time.
+async fn login_user(
+    provider: &str,
+    account: ProviderUser,
+    current: Option<i32>,
+    conn: &mut DbConn,
+) -> Result<User> {
+    for _ in 0..LOGIN_ATTEMPTS {
+        let account = account.clone();
+
+        let res = conn
+            .transaction::<_, AppError, _>(|conn| {
+                async move { link_account(provider, account, current, conn).await }.scope_boxed()
+            })
+            .await;
+
+        match res {
+            Err(AppError::Database(diesel::result::Error::DatabaseError(
+                DatabaseErrorKind::UniqueViolation,
+                _,
+            ))) => continue,
+
+            res => return res,
+        }
+    }
+
+    Err(AppError::BadRequest(
+        "Couldn't log in, please try again!".into(),
+    ))
+}
+
+/// Find (or create) the user a provider account belongs to, keeping their
+/// usernames up to date. If the account isn't linked to anyone yet and a user
+/// is already logged in, it gets linked to that user.
+async fn link_account(
+    provider: &str,
+    account: ProviderUser,
+    current: Option<i32>,
+    conn: &mut DbConn,
+) -> Result<User> {
+    if let Some(identity) = get_identity(provider, &account.id, conn).await? {
+        update(user_identities::table)
+            .filter(user_identities::id.eq(identity.id))
+            .set(user_identities::username.eq(&account.username))
+            .execute(conn)
+            .await?;
+
+        // Only follow the provider's username if the user still uses the old one.
+        if account.username != identity.username {
+            let username = unique_username(&account.username, conn).await?;
+
+            update(users::table)
+                .filter(users::id.eq(identity.user_id))
+                .filter(users::username.eq(&identity.username))
+                .set(users::username.eq(username))
+                .execute(conn)
+                .await?;
+        }
+
+        if let Some(github_id) = account.github_id {
+            update(users::table)
+                .filter(users::id.eq(identity.user_id))
+                .set(users::github_id.eq(github_id))
+                .execute(conn)
+                .await?;
+        }
+
+        return Ok(users::table
+            .find(identity.user_id)
+            .select(User::as_select())
+            .first(conn)
+            .await?);
+    }
+
+    let user = match current {
+        Some(id) => {
+            if let Some(github_id) = account.github_id {
                 update(users::table)
-                    .filter(users::id.eq(existing.id))
-                    .set(users::username.eq(me.login))
-                    .returning(User::as_returning())
-                    .get_result(&mut conn)
-                    .await?
-            } else {
-                let user = NewUser {
-                    username: me.login,
-                    github_id: me.id.0 as i32,
-                };
-
-                insert_into(users::table)
-                    .values(&user)
-                    .returning(User::as_returning())
-                    .get_result(&mut conn)
-                    .await?
-            };
-
-            let token = create_token(user.id, &state.pool).await?;
-
-            let cookie_value = format!(
-                "auth-token={}; HttpOnly; Path=/; Domain={}",
-                token.value,
-                sanitize_port(&host)
-            );
-
-            let mut response = Response::builder()
-                .status(StatusCode::TEMPORARY_REDIRECT)
-                .body(Body::new(token.value.clone()))?;
-
-            let cookie_header = HeaderValue::from_str(&cookie_value)?;
-
-            response.headers_mut().insert(SET_COOKIE, cookie_header);
-
-            if let Some(to) = to {
-                response.headers_mut().insert(
-                    LOCATION,
-                    HeaderValue::from_str(&format!("{}?token={}", to, token.value)).unwrap(),
-                );
-            } else {
-                response
-                    .headers_mut()
-                    .insert(LOCATION, HeaderValue::from_str("/api/v1/users/me").unwrap());
+                    .filter(users::id.eq(id))
+                    .set(users::github_id.eq(github_id))
+                    .execute(conn)
+                    .await?;
             }
 
-            return Ok(response);
+            users::table
+                .find(id)
+                .select(User::as_select())
+                .first(conn)
+                .await?
         }
 
-        Err(_) => {
-            let mut resp = Response::new(Body::empty());
-            *resp.status_mut() = StatusCode::TEMPORARY_REDIRECT;
-            return Ok(resp);
+        None => {
+            insert_into(users::table)
+                .values(&NewUser {
+                    username: unique_username(&account.username, conn).await?,
+                    github_id: account.github_id.unwrap_or(-1),
+                })
+                .returning(User::as_returning())
+                .get_result(conn)
+                .await?
         }
-    }
+    };
+
+    insert_into(user_identities::table)
+        .values(&NewUserIdentity {
+            user_id: user.id,
+            provider: provider.into(),
+            provider_id: account.id,
+            username: account.username,
+        })
+        .execute(conn)
+        .await?;
+
+    Ok(user)
 }
 
-fn sanitize_port(host: &str) -> String {
+pub fn sanitize_port(host: &str) -> String {
     match host.split_once(":") {
         Some((domain, _port)) => domain.to_string(),
         None => host.to_string(),
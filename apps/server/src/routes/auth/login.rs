@@ -1,27 +1,40 @@
 use std::collections::HashMap;
 
-use super::CALLBACK_URL;
-use crate::{state::AppState, util::scheme::Scheme, Result};
+use super::callback_url;
+use crate::{
+    auth::oauth_state::{safe_redirect, OAUTH_STATE_COOKIE, OAUTH_STATE_EXPIRE_TIME},
+    state::AppState,
+    util::scheme::Scheme,
+    Result,
+};
+use app_core::AppError;
 use axum::{
     body::Body,
-    extract::{Host, State},
-    http::{header::LOCATION, HeaderValue, StatusCode, Uri},
+    extract::{Host, Path, State},
+    http::{
+        header::{LOCATION, SET_COOKIE},
+        HeaderValue, StatusCode, Uri,
+    },
     response::Response,
 };
 use oauth2::{CsrfToken, RedirectUrl, Scope};
 
-/// GitHub Auth Login
+/// Auth Login
 ///
-/// Initiate the GitHub login flow.
+/// Initiate the login flow for a login provider.
+/// The redirect URI has to be on this site.
 #[utoipa::path(
     get,
-    path = "/api/v1/auth/github/login",
+    path = "/api/v1/auth/{provider}/login",
     tag = "Auth",
     params(
-        ("redirect_uri" = Option<String>, Query, description = "An optional custom URL to redirect to."),
+        ("provider" = String, Path, description = "The login provider's ID (like `github`)"),
+        ("redirect_uri" = Option<String>, Query, description = "An optional URL on this site to redirect to."),
     ),
     responses(
-        (status = 307, description = "Redirecting to GitHub for login"),
+        (status = 307, description = "Redirecting to the provider for login"),
+        (status = 400, description = "The redirect URI isn't on this site!"),
+        (status = 404, description = "That login provider isn't configured!"),
     ),
 )]
 #[debug_handler]
@@ -29,31 +42,34 @@ pub async fn login_handler(
     State(state): State<AppState>,
     Host(host): Host,
     Scheme(scheme): Scheme,
+    Path(provider): Path<String>,
     url: Uri,
 ) -> Result<Response> {
+    let provider = state.auth.get(provider)?;
     let query = url::form_urlencoded::parse(url.query().unwrap_or_default().as_bytes())
         .into_owned()
         .collect::<HashMap<String, String>>();
 
-    let callback_url_base = query
-        .get("redirect_uri")
-        .cloned()
-        .unwrap_or("/".to_string());
+    let to = match query.get("redirect_uri") {
+        Some(to) => safe_redirect(to, &scheme, &host).ok_or(AppError::BadRequest(
+            "The redirect URI must be on this site!".into(),
+        ))?,
 
-    let callback_url = format!(
-        "{}://{}{}?to={}",
-        scheme, host, CALLBACK_URL, callback_url_base
-    );
+        None => "/".into(),
+    };
+
+    let callback_url = format!("{}://{}{}", scheme, host, callback_url(provider.id()));
+
+    let client = provider
+        .client()
+        .clone()
+        .set_redirect_uri(RedirectUrl::new(callback_url)?);
 
-    let client = state
-        .auth
-        .set_redirect_uri(RedirectUrl::new(callback_url).unwrap());
+    let (login, login_cookie) = state.oauth_state.issue(provider.id(), to)?;
 
     let (mut authorize_url, _) = client
-        .authorize_url(CsrfToken::new_random)
-        .add_scope(Scope::new("user:email".to_string()))
-        .add_scope(Scope::new("read:user".to_string()))
-        .add_scope(Scope::new("public_repo".to_string()))
+        .authorize_url(|| CsrfToken::new(login.nonce))
+        .add_scopes(provider.scopes().into_iter().map(Scope::new))
         .url();
 
     authorize_url
@@ -62,9 +78,17 @@ pub async fn login_handler(
 
     let mut resp = Response::new(Body::empty());
 
+    let cookie_value = format!(
+        "{}={}; HttpOnly; Path=/api/v1/auth; Max-Age={}; SameSite=Lax",
+        OAUTH_STATE_COOKIE, login_cookie, OAUTH_STATE_EXPIRE_TIME
+    );
+
     resp.headers_mut()
         .insert(LOCATION, HeaderValue::from_str(authorize_url.as_str())?);
 
+    resp.headers_mut()
+        .insert(SET_COOKIE, HeaderValue::from_str(&cookie_value)?);
+
     *resp.status_mut() = StatusCode::TEMPORARY_REDIRECT;
 
     Ok(resp)
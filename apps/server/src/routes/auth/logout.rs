@@ -0,0 +1,72 @@
+use super::callback::sanitize_port;
+use crate::{
+    auth::{get_request_token, RequestToken},
+    state::AppState,
+    Result,
+};
+use axum::{
+    body::Body,
+    extract::{Host, State},
+    http::{header::SET_COOKIE, HeaderMap, HeaderValue},
+    response::Response,
+};
+use axum_extra::extract::CookieJar;
+use db::{api_tokens, user_tokens};
+use diesel::{delete, ExpressionMethods};
+use diesel_async::RunQueryDsl;
+
+/// Log Out
+///
+/// Delete the token the request was made with, and clear the auth cookie.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Logged out!", body = String),
+        (status = 401, description = "No token was given!"),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn logout_handler(
+    State(state): State<AppState>,
+    Host(host): Host,
+    jar: CookieJar,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let mut conn = state.pool.get().await?;
+
+    match get_request_token(&jar, &headers, &mut conn).await? {
+        RequestToken::Session(token) => {
+            delete(user_tokens::table)
+                .filter(user_tokens::id.eq(token.id))
+                .execute(&mut conn)
+                .await?;
+        }
+
+        RequestToken::Api(token) => {
+            delete(api_tokens::table)
+                .filter(api_tokens::id.eq(token.id))
+                .execute(&mut conn)
+                .await?;
+        }
+    }
+
+    let cookie_value = format!(
+        "auth-token=; HttpOnly; Path=/; Domain={}; Max-Age=0",
+        sanitize_port(&host)
+    );
+
+    let mut response =
+        Response::builder().body(Body::new("Logged out successfully!".to_string()))?;
+
+    response
+        .headers_mut()
+        .insert(SET_COOKIE, HeaderValue::from_str(&cookie_value)?);
+
+    Ok(response)
+}
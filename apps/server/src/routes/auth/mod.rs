@@ -4,17 +4,29 @@
 
 pub mod callback;
 pub mod login;
+pub mod logout;
+pub mod providers;
 
 use crate::state::AppState;
-use axum::{routing::get, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 use callback::callback_handler;
 use login::login_handler;
+use logout::logout_handler;
+use providers::providers_handler;
 
-pub const CALLBACK_URL: &str = "/api/v1/auth/github/callback";
+/// Get the path of a login provider's callback route.
+pub fn callback_url(provider: impl AsRef<str>) -> String {
+    format!("/api/v1/auth/{}/callback", provider.as_ref())
+}
 
 pub fn router(state: AppState) -> Router<AppState> {
     Router::new()
-        .route("/github/login", get(login_handler))
-        .route("/github/callback", get(callback_handler))
+        .route("/providers", get(providers_handler))
+        .route("/logout", post(logout_handler))
+        .route("/:provider/login", get(login_handler))
+        .route("/:provider/callback", get(callback_handler))
         .with_state(state)
 }
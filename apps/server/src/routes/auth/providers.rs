@@ -0,0 +1,18 @@
+use crate::{auth::providers::ProviderInfo, state::AppState, Result};
+use axum::{extract::State, Json};
+
+/// List Login Providers
+///
+/// List the login providers that are configured on this instance.
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/providers",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Found providers!", body = Vec<ProviderInfo>),
+    ),
+)]
+#[debug_handler]
+pub async fn providers_handler(State(state): State<AppState>) -> Result<Json<Vec<ProviderInfo>>> {
+    Ok(Json(state.auth.list()))
+}
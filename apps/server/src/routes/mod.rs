@@ -1,11 +1,19 @@
 pub mod api;
 pub mod auth;
 pub mod meta;
+pub mod orgs;
 pub mod pkg;
+pub mod resolve;
 pub mod users;
 
-use crate::{middleware::logger::logging_middleware, state::AppState};
-use axum::{middleware::from_fn, Router};
+use crate::{
+    middleware::{ip::client_ip_middleware, logger::logging_middleware},
+    state::AppState,
+};
+use axum::{
+    middleware::{from_fn, from_fn_with_state},
+    Router,
+};
 use jsglue::{glue::Glue, util::is_debug};
 
 pub fn create_router(state: AppState, glue: Glue) -> Router {
@@ -13,7 +21,10 @@ pub fn create_router(state: AppState, glue: Glue) -> Router {
         .nest("/api/v1/auth", auth::router(state.clone()))
         .nest("/api/v1/users", users::router(state.clone()))
         .nest("/api/v1/packages", pkg::router(state.clone()))
+        .nest("/api/v1/orgs", orgs::router(state.clone()))
         .nest("/api/v1/meta", meta::router(state.clone()))
+        .nest("/api/v1/resolve", resolve::router(state.clone()))
+        .layer(from_fn_with_state(state.clone(), client_ip_middleware))
         .layer(from_fn(logging_middleware))
         .with_state(state)
 }
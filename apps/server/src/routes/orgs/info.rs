@@ -0,0 +1,217 @@
+use super::members::check_org_role;
+use crate::{auth::get_user_from_req, state::AppState, Result};
+use app_core::AppError;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use chrono::Utc;
+use db::{
+    get_full_org, get_org, organization_members, organizations, packages, NewOrganization, OrgRole,
+    Organization, OrganizationData, OrganizationMember, TokenScope,
+};
+use diesel::{
+    delete, insert_into, update, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, ToSchema, ToResponse, Serialize, Deserialize,
+)]
+pub struct PartialOrganization {
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Get Organization
+///
+/// Get an organization and its members by its ID or slug.
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/{id}",
+    tag = "Organizations",
+    params(
+        ("id" = String, Path, description = "The organization's ID or slug."),
+    ),
+    responses(
+        (status = 200, description = "Found organization!", body = OrganizationData),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured! The organization may not exist!"),
+    ),
+)]
+#[debug_handler]
+pub async fn info_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<OrganizationData>> {
+    Ok(Json(get_full_org(id, &mut state.pool.get().await?).await?))
+}
+
+/// Create Organization
+///
+/// Create an organization. The current user becomes its owner.
+#[utoipa::path(
+    put,
+    path = "/api/v1/orgs",
+    tag = "Organizations",
+    responses(
+        (status = 200, description = "Organization created successfully!", body = OrganizationData),
+        (status = 400, description = "An organization with that slug already exists!"),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    request_body(content = NewOrganization, description = "Information about the organization to create"),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn create_handler(
+    jar: CookieJar,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    Json(body): Json<NewOrganization>,
+) -> Result<Json<OrganizationData>> {
+    let mut conn = state.pool.get().await?;
+    let user = get_user_from_req(&jar, &headers, &mut conn, TokenScope::Publish, None).await?;
+
+    if body.slug.trim().is_empty() || body.name.trim().is_empty() {
+        return Err(AppError::BadRequest(
+            "Organizations need a name and a slug!".into(),
+        ));
+    }
+
+    if organizations::table
+        .filter(organizations::slug.eq(&body.slug))
+        .select(organizations::id)
+        .first::<i32>(&mut conn)
+        .await
+        .optional()?
+        .is_some()
+    {
+        return Err(AppError::BadRequest(
+            "Organization with that slug already exists!".into(),
+        ));
+    }
+
+    let org = insert_into(organizations::table)
+        .values(&body)
+        .returning(organizations::id)
+        .get_result::<i32>(&mut conn)
+        .await?;
+
+    insert_into(organization_members::table)
+        .values(&OrganizationMember {
+            organization: org,
+            user_id: user.id,
+            role: OrgRole::Owner,
+        })
+        .execute(&mut conn)
+        .await?;
+
+    Ok(Json(get_full_org(org.to_string(), &mut conn).await?))
+}
+
+/// Update Organization
+///
+/// Update an organization's information. This needs the owner role.
+#[utoipa::path(
+    patch,
+    path = "/api/v1/orgs/{id}",
+    tag = "Organizations",
+    params(
+        ("id" = String, Path, description = "The organization's ID or slug."),
+    ),
+    responses(
+        (status = 200, description = "Organization updated successfully!", body = Organization),
+        (status = UNAUTHORIZED, description = "You do not have access to modify this organization!"),
+        (status = INTERNAL_SERVER_ERROR, description = "Error: organization might not exist, or another error occured!"),
+    ),
+    request_body(content = PartialOrganization, description = "The information to update"),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn update_handler(
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+    Json(data): Json<PartialOrganization>,
+) -> Result<Json<Organization>> {
+    let mut conn = state.pool.get().await?;
+    let org = get_org(id, &mut conn).await?;
+    let user =
+        get_user_from_req(&jar, &headers, &mut conn, TokenScope::ManageAuthors, None).await?;
+
+    check_org_role(org.id, &user, OrgRole::Owner, &mut conn).await?;
+
+    Ok(Json(
+        update(organizations::table)
+            .filter(organizations::id.eq(org.id))
+            .set((
+                organizations::name.eq(data.name.unwrap_or(org.name)),
+                organizations::description.eq(data.description.unwrap_or(org.description)),
+                organizations::updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .returning(Organization::as_returning())
+            .get_result(&mut conn)
+            .await?,
+    ))
+}
+
+/// Delete Organization
+///
+/// Delete an organization. This needs the owner role.
+/// Its packages aren't deleted, they just stop belonging to an organization.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/orgs/{id}",
+    tag = "Organizations",
+    params(
+        ("id" = String, Path, description = "The organization's ID or slug."),
+    ),
+    responses(
+        (status = 200, description = "Organization deleted successfully!", body = String),
+        (status = UNAUTHORIZED, description = "You do not have access to delete this organization!"),
+        (status = INTERNAL_SERVER_ERROR, description = "Error: organization might not exist, or another error occured!"),
+    ),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn delete_handler(
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<String> {
+    let mut conn = state.pool.get().await?;
+    let org = get_org(id, &mut conn).await?;
+    let user =
+        get_user_from_req(&jar, &headers, &mut conn, TokenScope::ManageAuthors, None).await?;
+
+    check_org_role(org.id, &user, OrgRole::Owner, &mut conn).await?;
+
+    let pkgs = packages::table
+        .filter(packages::organization.eq(org.id))
+        .select(packages::id)
+        .load::<i32>(&mut conn)
+        .await?;
+
+    delete(organizations::table)
+        .filter(organizations::id.eq(org.id))
+        .execute(&mut conn)
+        .await?;
+
+    for pkg in pkgs {
+        state.search.update_package(pkg, &mut conn).await?;
+    }
+
+    Ok("Deleted organization successfully!".into())
+}
@@ -0,0 +1,219 @@
+use crate::{auth::get_user_from_req, state::AppState, Result};
+use app_core::AppError;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use db::{
+    get_org, get_org_members, get_org_role, get_user, organization_members, DbConn, OrgMember,
+    OrgRole, OrganizationMember, TokenScope, User,
+};
+use diesel::{delete, insert_into, upsert::excluded, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+/// A request to add a member to an organization or change their role.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct SetMemberRequest {
+    /// The ID or username of the user.
+    pub user: String,
+
+    /// The user's role. Defaults to member.
+    #[serde(default)]
+    pub role: OrgRole,
+}
+
+/// Check that a user has at least `role` in an organization, returning their role.
+/// Admins can do anything, so they're treated as owners.
+pub async fn check_org_role(
+    org: i32,
+    user: &User,
+    role: OrgRole,
+    conn: &mut DbConn,
+) -> Result<OrgRole> {
+    if user.admin {
+        return Ok(OrgRole::Owner);
+    }
+
+    match get_org_role(org, user.id, conn).await? {
+        Some(it) if it >= role => Ok(it),
+        _ => Err(AppError::Unauthorized),
+    }
+}
+
+/// Make sure an organization would still have an owner without `user`.
+async fn check_other_owners(org: i32, user: i32, conn: &mut DbConn) -> Result<()> {
+    let owners = organization_members::table
+        .filter(organization_members::organization.eq(org))
+        .filter(organization_members::role.eq(OrgRole::Owner))
+        .filter(organization_members::user_id.ne(user))
+        .count()
+        .get_result::<i64>(conn)
+        .await?;
+
+    if owners == 0 {
+        return Err(AppError::BadRequest(
+            "Organizations need at least one owner!".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Get Organization Members
+///
+/// Get an organization's members and their roles.
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/{id}/members",
+    tag = "Organizations",
+    params(
+        ("id" = String, Path, description = "The organization's ID or slug."),
+    ),
+    responses(
+        (status = 200, description = "Found members!", body = Vec<OrgMember>),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured! The organization may not exist!"),
+    ),
+)]
+#[debug_handler]
+pub async fn list_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<OrgMember>>> {
+    let mut conn = state.pool.get().await?;
+    let org = get_org(id, &mut conn).await?;
+
+    Ok(Json(get_org_members(org.id, &mut conn).await?))
+}
+
+/// Set Organization Member
+///
+/// Add a member to an organization, or change a member's role.
+/// Admins can manage members, but only owners can manage admins and owners.
+#[utoipa::path(
+    put,
+    path = "/api/v1/orgs/{id}/members",
+    tag = "Organizations",
+    params(
+        ("id" = String, Path, description = "The organization's ID or slug."),
+    ),
+    responses(
+        (status = 200, description = "Members updated successfully!", body = Vec<OrgMember>),
+        (status = UNAUTHORIZED, description = "You do not have access to manage this member!"),
+        (status = BAD_REQUEST, description = "The organization would be left without an owner!"),
+        (status = INTERNAL_SERVER_ERROR, description = "Error: organization might not exist, or another error occured!"),
+    ),
+    request_body(content = SetMemberRequest, description = "The member to add or update."),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn set_handler(
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+    Json(body): Json<SetMemberRequest>,
+) -> Result<Json<Vec<OrgMember>>> {
+    let mut conn = state.pool.get().await?;
+    let org = get_org(id, &mut conn).await?;
+    let user =
+        get_user_from_req(&jar, &headers, &mut conn, TokenScope::ManageAuthors, None).await?;
+
+    let role = check_org_role(org.id, &user, OrgRole::Admin, &mut conn).await?;
+    let target = get_user(body.user, &mut conn).await?;
+    let current = get_org_role(org.id, target.id, &mut conn).await?;
+
+    if role < OrgRole::Owner && (body.role > OrgRole::Member || current > Some(OrgRole::Member)) {
+        return Err(AppError::Unauthorized);
+    }
+
+    if current == Some(OrgRole::Owner) && body.role != OrgRole::Owner {
+        check_other_owners(org.id, target.id, &mut conn).await?;
+    }
+
+    insert_into(organization_members::table)
+        .values(&OrganizationMember {
+            organization: org.id,
+            user_id: target.id,
+            role: body.role,
+        })
+        .on_conflict((
+            organization_members::organization,
+            organization_members::user_id,
+        ))
+        .do_update()
+        .set(organization_members::role.eq(excluded(organization_members::role)))
+        .execute(&mut conn)
+        .await?;
+
+    Ok(Json(get_org_members(org.id, &mut conn).await?))
+}
+
+/// Remove Organization Member
+///
+/// Remove a member from an organization.
+/// Anyone can leave an organization, as long as it still has an owner afterwards.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/orgs/{id}/members/{user}",
+    tag = "Organizations",
+    params(
+        ("id" = String, Path, description = "The organization's ID or slug."),
+        ("user" = String, Path, description = "The ID or username of the member to remove."),
+    ),
+    responses(
+        (status = 200, description = "Members updated successfully!", body = Vec<OrgMember>),
+        (status = UNAUTHORIZED, description = "You do not have access to remove this member!"),
+        (status = BAD_REQUEST, description = "The user isn't a member, or the organization would be left without an owner!"),
+        (status = INTERNAL_SERVER_ERROR, description = "Error: organization might not exist, or another error occured!"),
+    ),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn remove_handler(
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path((id, member)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<OrgMember>>> {
+    let mut conn = state.pool.get().await?;
+    let org = get_org(id, &mut conn).await?;
+    let user =
+        get_user_from_req(&jar, &headers, &mut conn, TokenScope::ManageAuthors, None).await?;
+
+    let target = get_user(member, &mut conn).await?;
+
+    let Some(current) = get_org_role(org.id, target.id, &mut conn).await? else {
+        return Err(AppError::BadRequest(
+            "That user is not a member of the organization!".into(),
+        ));
+    };
+
+    if target.id != user.id {
+        let needed = match current {
+            OrgRole::Member => OrgRole::Admin,
+            _ => OrgRole::Owner,
+        };
+
+        check_org_role(org.id, &user, needed, &mut conn).await?;
+    }
+
+    if current == OrgRole::Owner {
+        check_other_owners(org.id, target.id, &mut conn).await?;
+    }
+
+    delete(organization_members::table)
+        .filter(organization_members::organization.eq(org.id))
+        .filter(organization_members::user_id.eq(target.id))
+        .execute(&mut conn)
+        .await?;
+
+    Ok(Json(get_org_members(org.id, &mut conn).await?))
+}
@@ -0,0 +1,29 @@
+pub mod info;
+pub mod members;
+pub mod pkg;
+pub mod search;
+
+use crate::state::AppState;
+use axum::{
+    routing::{delete, get, put},
+    Router,
+};
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", put(info::create_handler))
+        .route("/search", get(search::search_handler))
+        .route(
+            "/:id",
+            get(info::info_handler)
+                .patch(info::update_handler)
+                .delete(info::delete_handler),
+        )
+        .route("/:id/packages", get(pkg::list_handler))
+        .route(
+            "/:id/members",
+            get(members::list_handler).put(members::set_handler),
+        )
+        .route("/:id/members/:user", delete(members::remove_handler))
+        .with_state(state)
+}
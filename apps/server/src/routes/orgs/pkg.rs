@@ -0,0 +1,59 @@
+use crate::{routes::users::pkg::visible_packages, state::AppState, Result};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use db::{get_org, packages, users, Package, PackageAuthor, PackageData, User};
+use diesel::{BelongingToDsl, ExpressionMethods, GroupedBy, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+/// Get Organization Packages
+///
+/// Get the packages an organization owns.
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/{id}/packages",
+    tag = "Organizations",
+    params(
+        ("id" = String, Path, description = "The organization's ID or slug."),
+    ),
+    responses(
+        (status = 200, description = "Found packages!", body = Vec<PackageData>),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured! The organization may not exist!"),
+    ),
+)]
+#[debug_handler]
+pub async fn list_handler(
+    jar: CookieJar,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<PackageData>>> {
+    let mut conn = state.pool.get().await?;
+    let org = get_org(id, &mut conn).await?;
+
+    let pkgs = packages::table
+        .filter(packages::organization.eq(org.id))
+        .select(Package::as_select())
+        .load(&mut conn)
+        .await?;
+
+    let authors: Vec<(PackageAuthor, User)> = PackageAuthor::belonging_to(&pkgs)
+        .inner_join(users::table)
+        .select((PackageAuthor::as_select(), User::as_select()))
+        .load(&mut conn)
+        .await?;
+
+    let res = authors
+        .grouped_by(&pkgs)
+        .into_iter()
+        .zip(pkgs)
+        .map(|(authors, pkg)| pkg.with_authors(authors.into_iter().map(|(_, user)| user).collect()))
+        .collect::<Vec<_>>();
+
+    Ok(Json(
+        visible_packages(res, &jar, &headers, &mut conn).await?,
+    ))
+}
@@ -0,0 +1,34 @@
+use crate::{state::AppState, Result};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use db::{search_orgs, Organization};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+pub struct SearchQuery {
+    q: String,
+}
+
+/// Search Organizations
+///
+/// Search for organizations by a case-insensitive string in their name or slug.
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/search",
+    tag = "Organizations",
+    params(
+        ("q" = String, Query, description = "The string to search for in the name or slug."),
+    ),
+    responses(
+        (status = 200, description = "Finished successfully!", body = Vec<Organization>),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+)]
+#[debug_handler]
+pub async fn search_handler(
+    State(state): State<AppState>,
+    Query(SearchQuery { q }): Query<SearchQuery>,
+) -> Result<Json<Vec<Organization>>> {
+    Ok(Json(search_orgs(q, &mut state.pool.get().await?).await?))
+}
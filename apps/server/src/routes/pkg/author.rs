@@ -1,21 +1,90 @@
-use crate::{auth::get_user_from_req, state::AppState, Result};
+use crate::{
+    auth::package::{perm, ReadablePackage, WritablePackage},
+    state::AppState,
+    Result,
+};
 use app_core::AppError;
 use axum::{
     body::Body,
-    extract::{Path, State},
-    http::{HeaderMap, StatusCode},
+    extract::{Path, Query, State},
     response::Response,
+    Json,
 };
-use axum_extra::extract::CookieJar;
 use db::{
-    get_full_package, get_package, get_user, package_authors, PackageAuthor, PackageData,
-    PackageVisibility, User,
-};
-use diesel::{
-    dsl::delete, insert_into, BoolExpressionMethods, ExpressionMethods, QueryDsl, SelectableHelper,
+    get_author_role, get_full_package, get_package_authors, get_user, has_package_permission,
+    package_authors, AuthorData, AuthorRole, DbConn, Package, PackageAuthor, PackageData,
+    PackagePermission, User,
 };
+use diesel::{dsl::delete, insert_into, update, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 
+/// The options for adding an author to a package.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct AddAuthorQuery {
+    /// The author's role. Defaults to maintainer.
+    #[serde(default)]
+    pub role: AuthorRole,
+}
+
+/// A request to change an author's role.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct SetAuthorRoleRequest {
+    /// The author's new role.
+    pub role: AuthorRole,
+}
+
+/// Make sure a user can give out or take away a role, since only
+/// people who could do everything to a package can manage its owners.
+async fn check_role_change(
+    user: &User,
+    pkg: &Package,
+    role: AuthorRole,
+    conn: &mut DbConn,
+) -> Result<()> {
+    if role == AuthorRole::Owner
+        && !has_package_permission(
+            user,
+            pkg.id,
+            pkg.organization,
+            PackagePermission::Manage,
+            conn,
+        )
+        .await?
+    {
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(())
+}
+
+/// Make sure a package would still have an owner without `user`.
+/// Packages in an organization don't need one, since its admins can manage them.
+async fn check_other_owners(pkg: &Package, user: i32, conn: &mut DbConn) -> Result<()> {
+    if pkg.organization.is_some() {
+        return Ok(());
+    }
+
+    let owners = package_authors::table
+        .filter(package_authors::package.eq(pkg.id))
+        .filter(package_authors::role.eq(AuthorRole::Owner))
+        .filter(package_authors::user_id.ne(user))
+        .count()
+        .get_result::<i64>(conn)
+        .await?;
+
+    if owners == 0 {
+        return Err(AppError::BadRequest(
+            "Packages need at least one owner!".into(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// Get Package Authors
 ///
 /// Get a package's authors by its ID or slug.
@@ -24,7 +93,7 @@ use diesel_async::RunQueryDsl;
     path = "/api/v1/packages/{id}/authors",
     tag = "Packages",
     responses(
-        (status = 200, description = "A list of package authors", body = Vec<User>),
+        (status = 200, description = "A list of package authors", body = Vec<AuthorData>),
         (status = INTERNAL_SERVER_ERROR, description = "Error: package might not exist, or another error occured!"),
     ),
     params(
@@ -33,38 +102,30 @@ use diesel_async::RunQueryDsl;
 )]
 #[debug_handler]
 pub async fn list_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(id): Path<String>,
+    ReadablePackage { pkg, .. }: ReadablePackage,
     State(state): State<AppState>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let pkg = get_full_package(id, &mut conn).await?;
-
-    if pkg.visibility == PackageVisibility::Private {
-        match get_user_from_req(&jar, &headers, &mut conn).await {
-            Ok(user) => {
-                if !pkg.authors.iter().any(|v| v.github_id == user.github_id) && !user.admin {
-                    return Err(AppError::NotFound);
-                }
-            }
-
-            Err(_) => return Err(AppError::NotFound),
-        }
-    }
 
     Ok(Response::builder()
         .header("Content-Type", "application/json")
-        .body(Body::new(serde_json::to_string(&pkg.authors)?))?)
+        .body(Body::new(serde_json::to_string(
+            &get_package_authors(pkg.id, &mut conn).await?,
+        )?))?)
 }
 
 /// Add Package Author
 ///
 /// Add an author to a package.
+/// Only users who can manage the package's owners can add another owner.
 #[utoipa::path(
     put,
     path = "/api/v1/packages/{id}/authors",
     tag = "Packages",
+    params(
+        ("id" = String, Path, description = "The package ID or slug"),
+        ("role" = Option<AuthorRole>, Query, description = "The author's role - defaults to `maintainer`"),
+    ),
     responses(
         (status = 200, description = "Package updated successfully!", body = PackageData),
         (status = UNAUTHORIZED, description = "You do not have access to modify this package!"),
@@ -78,42 +139,31 @@ pub async fn list_handler(
 )]
 #[debug_handler]
 pub async fn add_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(id): Path<String>,
+    WritablePackage { pkg, user, .. }: WritablePackage<perm::ManageAuthors>,
     State(state): State<AppState>,
+    Query(AddAuthorQuery { role }): Query<AddAuthorQuery>,
     body: String,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
-    let pkg = get_package(id, &mut conn).await?;
-
-    let authors = package_authors::table
-        .filter(package_authors::package.eq(pkg.id))
-        .select(PackageAuthor::as_select())
-        .load(&mut conn)
-        .await?;
 
-    if authors.iter().find(|v| v.user_id == user.id).is_none() && !user.admin {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::empty())?);
-    }
+    check_role_change(&user, &pkg, role, &mut conn).await?;
 
     let to_add = get_user(body, &mut conn).await?;
 
-    if authors.iter().find(|v| v.user_id == to_add.id).is_some() {
-        return Ok(Response::builder()
-            .status(StatusCode::BAD_REQUEST)
-            .body(Body::new(
-                "Author is already a member of the project!".to_string(),
-            ))?);
+    if get_author_role(pkg.id, to_add.id, &mut conn)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::BadRequest(
+            "Author is already a member of the project!".to_string(),
+        ));
     }
 
     insert_into(package_authors::table)
         .values(&PackageAuthor {
             package: pkg.id,
             user_id: to_add.id,
+            role,
         })
         .execute(&mut conn)
         .await?;
@@ -127,9 +177,67 @@ pub async fn add_handler(
         )?))?)
 }
 
+/// Set Package Author Role
+///
+/// Change an author's role in a package.
+/// Only users who can manage the package's owners can change an owner's role or make someone an owner.
+#[utoipa::path(
+    patch,
+    path = "/api/v1/packages/{id}/authors/{user}",
+    tag = "Packages",
+    params(
+        ("id" = String, Path, description = "The package ID or slug"),
+        ("user" = String, Path, description = "The ID or username of the author"),
+    ),
+    responses(
+        (status = 200, description = "Authors updated successfully!", body = Vec<AuthorData>),
+        (status = UNAUTHORIZED, description = "You do not have access to change this author's role!"),
+        (status = BAD_REQUEST, description = "The user is not a member of the project, or it would be left without an owner!"),
+        (status = INTERNAL_SERVER_ERROR, description = "Error: package might not exist, or another error occured!"),
+    ),
+    request_body(content = SetAuthorRoleRequest, description = "The author's new role."),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn set_role_handler(
+    Path((_id, author)): Path<(String, String)>,
+    WritablePackage { pkg, user, .. }: WritablePackage<perm::ManageAuthors>,
+    State(state): State<AppState>,
+    Json(body): Json<SetAuthorRoleRequest>,
+) -> Result<Json<Vec<AuthorData>>> {
+    let mut conn = state.pool.get().await?;
+
+    let target = get_user(author, &mut conn).await?;
+
+    let Some(current) = get_author_role(pkg.id, target.id, &mut conn).await? else {
+        return Err(AppError::BadRequest(
+            "Author is not a member of the project!".to_string(),
+        ));
+    };
+
+    check_role_change(&user, &pkg, current, &mut conn).await?;
+    check_role_change(&user, &pkg, body.role, &mut conn).await?;
+
+    if current == AuthorRole::Owner && body.role != AuthorRole::Owner {
+        check_other_owners(&pkg, target.id, &mut conn).await?;
+    }
+
+    update(package_authors::table)
+        .filter(package_authors::package.eq(pkg.id))
+        .filter(package_authors::user_id.eq(target.id))
+        .set(package_authors::role.eq(body.role))
+        .execute(&mut conn)
+        .await?;
+
+    Ok(Json(get_package_authors(pkg.id, &mut conn).await?))
+}
+
 /// Remove Package Author
 ///
 /// Remove an author from a package.
+/// Only users who can manage the package's owners can remove an owner.
 #[utoipa::path(
     delete,
     path = "/api/v1/packages/{id}/authors",
@@ -137,7 +245,7 @@ pub async fn add_handler(
     responses(
         (status = 200, description = "Package updated successfully!", body = PackageData),
         (status = UNAUTHORIZED, description = "You do not have access to modify this package!"),
-        (status = BAD_REQUEST, description = "The user is not a member of the project!"),
+        (status = BAD_REQUEST, description = "The user is not a member of the project, or it would be left without an owner!"),
         (status = INTERNAL_SERVER_ERROR, description = "Error: package might not exist, or another error occured!"),
     ),
     request_body(content = String, description = "The ID/username of the author to remove."),
@@ -147,44 +255,29 @@ pub async fn add_handler(
 )]
 #[debug_handler]
 pub async fn remove_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(id): Path<String>,
+    WritablePackage { pkg, user, .. }: WritablePackage<perm::ManageAuthors>,
     State(state): State<AppState>,
     body: String,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
-    let pkg = get_package(id, &mut conn).await?;
 
-    let authors = package_authors::table
-        .filter(package_authors::package.eq(pkg.id))
-        .select(PackageAuthor::as_select())
-        .load(&mut conn)
-        .await?;
+    let to_remove = get_user(body, &mut conn).await?;
 
-    if authors.iter().find(|v| v.user_id == user.id).is_none() && !user.admin {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::empty())?);
-    }
+    let Some(current) = get_author_role(pkg.id, to_remove.id, &mut conn).await? else {
+        return Err(AppError::BadRequest(
+            "Author is not a member of the project!".to_string(),
+        ));
+    };
 
-    let to_remove = get_user(body, &mut conn).await?;
+    check_role_change(&user, &pkg, current, &mut conn).await?;
 
-    if authors.iter().find(|v| v.user_id == to_remove.id).is_none() {
-        return Ok(Response::builder()
-            .status(StatusCode::BAD_REQUEST)
-            .body(Body::new(
-                "Author is not a member of the project!".to_string(),
-            ))?);
+    if current == AuthorRole::Owner {
+        check_other_owners(&pkg, to_remove.id, &mut conn).await?;
     }
 
     delete(package_authors::table)
-        .filter(
-            package_authors::package
-                .eq(pkg.id)
-                .and(package_authors::user_id.eq(to_remove.id)),
-        )
+        .filter(package_authors::package.eq(pkg.id))
+        .filter(package_authors::user_id.eq(to_remove.id))
         .execute(&mut conn)
         .await?;
 
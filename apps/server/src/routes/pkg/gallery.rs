@@ -1,27 +1,25 @@
-use anyhow::anyhow;
 use app_core::AppError;
 use axum::{
     body::Body,
     extract::{Multipart, Path, State},
-    http::{HeaderMap, StatusCode},
     response::Response,
     Json,
 };
-use axum_extra::extract::CookieJar;
 use chrono::Utc;
 use db::{
-    gallery_images, get_full_package, get_gallery, get_gallery_image, get_package, package_authors,
-    packages, GalleryImage, NewGalleryImage, Package, PackageAuthor, PackageVisibility,
-    PublicGalleryImage,
+    gallery_images, get_gallery, get_gallery_image, packages, GalleryImage, NewGalleryImage,
+    Package, PublicGalleryImage,
 };
 use diesel::{delete, insert_into, update, ExpressionMethods, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
-use sha1::{Digest, Sha1};
 
 use crate::{
-    auth::get_user_from_req,
+    auth::package::{perm, ReadablePackage, WritablePackage},
     state::AppState,
-    util::gallery::{get_image, transform_gallery, transform_gallery_image},
+    util::{
+        gallery::{get_image, transform_gallery, transform_gallery_image},
+        upload::{receive_file, receive_text},
+    },
     Result,
 };
 
@@ -82,30 +80,15 @@ pub struct PartialGalleryImage {
 )]
 #[debug_handler]
 pub async fn list_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(id): Path<String>,
+    ReadablePackage { pkg, .. }: ReadablePackage,
     State(state): State<AppState>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let pkg = get_full_package(id.clone(), &mut conn).await?;
-
-    if pkg.visibility == PackageVisibility::Private {
-        match get_user_from_req(&jar, &headers, &mut conn).await {
-            Ok(user) => {
-                if !pkg.authors.iter().any(|v| v.github_id == user.github_id) && !user.admin {
-                    return Err(AppError::NotFound);
-                }
-            }
-
-            Err(_) => return Err(AppError::NotFound),
-        }
-    }
 
     Ok(Response::builder()
         .header("Content-Type", "application/json")
         .body(Body::new(serde_json::to_string(
-            &transform_gallery(get_gallery(id, &mut conn).await?).await?,
+            &transform_gallery(get_gallery(pkg.id.to_string(), &mut conn).await?).await?,
         )?))?)
 }
 
@@ -127,28 +110,17 @@ pub async fn list_handler(
 )]
 #[debug_handler]
 pub async fn info_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path((id, image)): Path<(String, String)>,
+    Path((_id, image)): Path<(String, String)>,
+    ReadablePackage { pkg, .. }: ReadablePackage,
     State(state): State<AppState>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let pkg = get_full_package(id.clone(), &mut conn).await?;
-
-    if pkg.visibility == PackageVisibility::Private {
-        match get_user_from_req(&jar, &headers, &mut conn).await {
-            Ok(user) => {
-                if !pkg.authors.iter().any(|v| v.github_id == user.github_id) && !user.admin {
-                    return Err(AppError::NotFound);
-                }
-            }
+    let img = get_gallery_image(image, &mut conn).await?;
 
-            Err(_) => return Err(AppError::NotFound),
-        }
+    if img.package != pkg.id {
+        return Err(AppError::NotFound);
     }
 
-    let img = get_gallery_image(image, &mut conn).await?;
-
     Ok(Response::builder()
         .header("Content-Type", "application/json")
         .body(Body::new(serde_json::to_string(
@@ -189,6 +161,7 @@ pub async fn s3_image_handler(
     tag = "Gallery",
     responses(
         (status = 200, description = "Created gallery image!", body = PublicGalleryImage),
+        (status = PAYLOAD_TOO_LARGE, description = "The image or a field is over the server's size limit, or more than one image was sent!"),
         (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
     ),
     params(
@@ -201,69 +174,54 @@ pub async fn s3_image_handler(
 )]
 #[debug_handler]
 pub async fn upload_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(id): Path<String>,
+    WritablePackage { pkg, .. }: WritablePackage<perm::Gallery>,
     State(state): State<AppState>,
     mut data: Multipart,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
-    let pkg = get_package(id, &mut conn).await?;
-
-    let authors = package_authors::table
-        .filter(package_authors::package.eq(pkg.id))
-        .select(PackageAuthor::as_select())
-        .load(&mut conn)
-        .await?;
-
-    if authors.iter().find(|v| v.user_id == user.id).is_none() && !user.admin {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::empty())?);
-    }
 
     let mut name = None;
     let mut description = None;
     let mut ordering = None;
     let mut file = None;
 
-    while let Ok(Some(field)) = data.next_field().await {
-        match field
-            .name()
-            .ok_or(anyhow!("Could not find a name for a field!"))?
-        {
-            "name" => name = Some(field.text().await?),
-            "description" => description = Some(field.text().await?),
-            "ordering" => ordering = Some(field.text().await?),
-            "file" => file = Some(field.bytes().await?),
+    while let Some(field) = data.next_field().await? {
+        match field.name().ok_or(AppError::BadRequest(
+            "Could not find a name for a field!".into(),
+        ))? {
+            "name" => name = Some(receive_text(field).await?),
+            "description" => description = Some(receive_text(field).await?),
+            "ordering" => ordering = Some(receive_text(field).await?),
+            "file" => {
+                if file.is_some() {
+                    return Err(AppError::TooManyFiles(1));
+                }
+
+                file = Some(receive_file(field, state.config.server.max_image_size).await?);
+            }
             _ => {}
         }
     }
 
     if name.is_none() {
-        Err(anyhow!("Missing field: 'name'"))?;
+        return Err(AppError::BadRequest("Missing field: 'name'".into()));
     }
 
     if file.is_none() {
-        Err(anyhow!("Missing field: 'file'"))?;
+        return Err(AppError::BadRequest("Missing field: 'file'".into()));
     }
 
     let name = name.unwrap();
     let ordering = ordering.unwrap_or("-1".into()).parse()?;
     let file = file.unwrap();
-    let file_format = imghdr::from_bytes(&file).ok_or(anyhow!("Invalid image file!"))?;
-    let mut hasher = Sha1::new();
-
-    hasher.update(&file);
-
-    let file_id = format!("{:x}", hasher.finalize());
-    let file_name = format!("{}.{}", file_id, file_format);
+    let file_format = imghdr::from_file(file.path())?
+        .ok_or(AppError::BadRequest("Invalid image file!".into()))?;
+    let file_name = format!("{}.{}", file.hashes.sha1, file_format);
 
     state
         .buckets
         .gallery
-        .put_object(format!("/{}", file_name), &file)
+        .put_file(&file_name, file.path())
         .await?;
 
     let data = NewGalleryImage {
@@ -316,26 +274,15 @@ pub async fn upload_handler(
 )]
 #[debug_handler]
 pub async fn delete_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path((package, image)): Path<(String, String)>,
+    Path((_id, image)): Path<(String, String)>,
+    WritablePackage { pkg, .. }: WritablePackage<perm::Gallery>,
     State(state): State<AppState>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
-    let pkg = get_package(package, &mut conn).await?;
     let img = get_gallery_image(image, &mut conn).await?;
 
-    let authors = package_authors::table
-        .filter(package_authors::package.eq(pkg.id))
-        .select(PackageAuthor::as_select())
-        .load(&mut conn)
-        .await?;
-
-    if authors.iter().find(|v| v.user_id == user.id).is_none() && !user.admin {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::empty())?);
+    if img.package != pkg.id {
+        return Err(AppError::NotFound);
     }
 
     let all_referencing = gallery_images::table
@@ -345,11 +292,7 @@ pub async fn delete_handler(
         .await?;
 
     if all_referencing.len() <= 1 {
-        state
-            .buckets
-            .gallery
-            .delete_object(format!("/{}", img.s3_id))
-            .await?;
+        state.buckets.gallery.delete(&img.s3_id).await?;
     }
 
     delete(gallery_images::table)
@@ -382,27 +325,16 @@ pub async fn delete_handler(
 )]
 #[debug_handler]
 pub async fn update_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path((package, image)): Path<(String, String)>,
+    Path((_id, image)): Path<(String, String)>,
+    WritablePackage { pkg, .. }: WritablePackage<perm::Gallery>,
     State(state): State<AppState>,
     Json(data): Json<PartialGalleryImage>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
-    let pkg = get_package(package, &mut conn).await?;
     let img = get_gallery_image(image, &mut conn).await?;
 
-    let authors = package_authors::table
-        .filter(package_authors::package.eq(pkg.id))
-        .select(PackageAuthor::as_select())
-        .load(&mut conn)
-        .await?;
-
-    if authors.iter().find(|v| v.user_id == user.id).is_none() && !user.admin {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::empty())?);
+    if img.package != pkg.id {
+        return Err(AppError::NotFound);
     }
 
     let img = update(gallery_images::table)
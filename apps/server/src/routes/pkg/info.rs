@@ -1,20 +1,17 @@
 use crate::{
-    auth::get_user_from_req, routes::users::pkg::clear_user_cache, state::AppState, Result,
+    auth::package::{perm, ReadablePackage, WritablePackage},
+    routes::users::pkg::clear_user_cache,
+    state::AppState,
+    util::relations::can_see,
+    Result,
 };
 use app_core::AppError;
-use axum::{
-    body::Body,
-    extract::{Path, State},
-    http::{HeaderMap, StatusCode},
-    response::Response,
-    Json,
-};
-use axum_extra::extract::CookieJar;
+use axum::{body::Body, extract::State, response::Response, Json};
 use db::{
-    get_full_package, get_package, package_authors, packages, Package, PackageAuthor, PackageData,
-    PackageVisibility,
+    get_full_package, get_org, get_org_role, get_package, has_package_permission, packages,
+    OrgRole, Package, PackageData, PackagePermission, PackageVisibility,
 };
-use diesel::{delete, update, ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel::{delete, update, ExpressionMethods, SelectableHelper};
 use diesel_async::RunQueryDsl;
 
 #[derive(
@@ -47,6 +44,20 @@ pub struct PartialPackage {
 
     #[serde(default)]
     pub tags: Option<Vec<String>>,
+
+    /// A deprecation message for the package. An empty message removes the deprecation.
+    #[serde(default)]
+    pub deprecation: Option<String>,
+
+    /// The ID or slug of the package that replaces this one. An empty value removes it.
+    #[serde(default)]
+    pub replacement: Option<String>,
+
+    /// The ID or slug of the organization that should own the package.
+    /// An empty value removes it from its organization.
+    /// This needs full control of the package and the admin role in the new organization.
+    #[serde(default)]
+    pub organization: Option<String>,
 }
 
 /// Get Package
@@ -64,28 +75,8 @@ pub struct PartialPackage {
         ("id" = String, Path, description = "The package ID or slug"),
     ),
 )]
-#[debug_handler]
-pub async fn info_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(id): Path<String>,
-    State(state): State<AppState>,
-) -> Result<Response> {
-    let mut conn = state.pool.get().await?;
-    let pkg = get_full_package(id, &mut conn).await?;
-
-    if pkg.visibility == PackageVisibility::Private {
-        match get_user_from_req(&jar, &headers, &mut conn).await {
-            Ok(user) => {
-                if !pkg.authors.iter().any(|v| v.github_id == user.github_id) && !user.admin {
-                    return Err(AppError::NotFound);
-                }
-            }
-
-            Err(_) => return Err(AppError::NotFound),
-        }
-    }
-
+#[debug_handler(state = AppState)]
+pub async fn info_handler(ReadablePackage { pkg, .. }: ReadablePackage) -> Result<Response> {
     Ok(Response::builder()
         .header("Content-Type", "application/json")
         .body(Body::new(serde_json::to_string(&pkg)?))?)
@@ -109,27 +100,63 @@ pub async fn info_handler(
 )]
 #[debug_handler]
 pub async fn update_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(id): Path<String>,
+    WritablePackage { pkg, user, .. }: WritablePackage<perm::Edit>,
     State(state): State<AppState>,
     Json(data): Json<PartialPackage>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
-    let pkg = get_package(id, &mut conn).await?;
 
-    let authors = package_authors::table
-        .filter(package_authors::package.eq(pkg.id))
-        .select(PackageAuthor::as_select())
-        .load(&mut conn)
-        .await?;
+    let replacement = match data.replacement {
+        Some(id) if id.is_empty() => None,
+        Some(id) => {
+            let other = get_package(id, &mut conn).await?;
 
-    if authors.iter().find(|v| v.user_id == user.id).is_none() && !user.admin {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::empty())?);
-    }
+            // Private packages can only be linked to by people who can see them.
+            if !can_see(&other, Some(&user), &mut conn).await? {
+                return Err(AppError::NotFound);
+            }
+
+            if other.id == pkg.id {
+                return Err(AppError::BadRequest(
+                    "A package cannot be its own replacement!".to_string(),
+                ));
+            }
+
+            Some(other.id)
+        }
+        None => pkg.replacement,
+    };
+
+    let organization = match data.organization {
+        Some(id) => {
+            if !has_package_permission(
+                &user,
+                pkg.id,
+                pkg.organization,
+                PackagePermission::Manage,
+                &mut conn,
+            )
+            .await?
+            {
+                return Err(AppError::Unauthorized);
+            }
+
+            if id.is_empty() {
+                None
+            } else {
+                let org = get_org(id, &mut conn).await?;
+                let role = get_org_role(org.id, user.id, &mut conn).await?;
+
+                if !user.admin && role.is_none_or(|it| it < OrgRole::Admin) {
+                    return Err(AppError::Unauthorized);
+                }
+
+                Some(org.id)
+            }
+        }
+
+        None => pkg.organization,
+    };
 
     let pkg = update(packages::table)
         .filter(packages::id.eq(pkg.id))
@@ -146,6 +173,12 @@ pub async fn update_handler(
                 .tags
                 .map(|v| v.into_iter().map(|v| Some(v)).collect::<Vec<_>>())
                 .unwrap_or(pkg.tags)),
+            packages::deprecation.eq(data
+                .deprecation
+                .map(|v| Some(v).filter(|v| !v.is_empty()))
+                .unwrap_or(pkg.deprecation)),
+            packages::replacement.eq(replacement),
+            packages::organization.eq(organization),
         ))
         .returning(Package::as_select())
         .get_result(&mut conn)
@@ -178,26 +211,10 @@ pub async fn update_handler(
 )]
 #[debug_handler]
 pub async fn delete_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(id): Path<String>,
+    WritablePackage { pkg, user, .. }: WritablePackage<perm::Manage>,
     State(state): State<AppState>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
-    let pkg = get_package(id, &mut conn).await?;
-
-    let authors = package_authors::table
-        .filter(package_authors::package.eq(pkg.id))
-        .select(PackageAuthor::as_select())
-        .load(&mut conn)
-        .await?;
-
-    if authors.iter().find(|v| v.user_id == user.id).is_none() && !user.admin {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::empty())?);
-    }
 
     delete(packages::table)
         .filter(packages::id.eq(pkg.id))
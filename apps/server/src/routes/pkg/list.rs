@@ -1,16 +1,12 @@
 use crate::{
     auth::get_user_from_req, routes::users::pkg::clear_user_cache, state::AppState, Result,
 };
-use axum::{
-    body::Body,
-    extract::State,
-    http::{HeaderMap, StatusCode},
-    response::Response,
-    Json,
-};
+use app_core::AppError;
+use axum::{body::Body, extract::State, http::HeaderMap, response::Response, Json};
 use axum_extra::extract::CookieJar;
 use db::{
-    get_full_package, package_authors, packages, NewPackage, Package, PackageAuthor, PackageData,
+    get_full_package, get_org_role, package_authors, packages, AuthorRole, NewPackage, OrgRole,
+    Package, PackageAuthor, PackageData, TokenScope,
 };
 use diesel::{insert_into, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
@@ -40,7 +36,7 @@ pub async fn create_handler(
     Json(body): Json<NewPackage>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
+    let user = get_user_from_req(&jar, &headers, &mut conn, TokenScope::Publish, None).await?;
 
     if let Some(_) = packages::table
         .filter(packages::slug.eq(body.slug.clone()))
@@ -49,11 +45,17 @@ pub async fn create_handler(
         .await
         .optional()?
     {
-        return Ok(Response::builder()
-            .status(StatusCode::BAD_REQUEST)
-            .body(Body::new(
-                "Package with that slug already exists!".to_string(),
-            ))?);
+        return Err(AppError::BadRequest(
+            "Package with that slug already exists!".to_string(),
+        ));
+    }
+
+    if let Some(org) = body.organization {
+        let role = get_org_role(org, user.id, &mut conn).await?;
+
+        if !user.admin && role.is_none_or(|it| it < OrgRole::Admin) {
+            return Err(AppError::Unauthorized);
+        }
     }
 
     let pkg = insert_into(packages::table)
@@ -66,6 +68,7 @@ pub async fn create_handler(
         .values(&PackageAuthor {
             package: pkg.id,
             user_id: user.id,
+            role: AuthorRole::Owner,
         })
         .execute(&mut conn)
         .await?;
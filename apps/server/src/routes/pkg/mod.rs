@@ -3,9 +3,13 @@ pub mod gallery;
 pub mod info;
 pub mod list;
 pub mod search;
+pub mod stats;
 pub mod ver;
 
-use crate::state::AppState;
+use crate::{
+    state::AppState,
+    util::upload::{upload_body_limit, MAX_UPLOAD_FILES},
+};
 use axum::{
     routing::{delete, get, patch, put},
     Router,
@@ -22,8 +26,16 @@ pub fn router(state: AppState) -> Router<AppState> {
         .route("/:id/authors", get(author::list_handler))
         .route("/:id/authors", put(author::add_handler))
         .route("/:id/authors", delete(author::remove_handler))
+        .route("/:id/authors/:user", patch(author::set_role_handler))
         .route("/:id/versions", get(ver::list_handler))
-        .route("/:id/versions", put(ver::create_handler))
+        // Uploads also check each file and field's size as they're received.
+        .route(
+            "/:id/versions",
+            put(ver::create_handler).layer(upload_body_limit(
+                state.config.server.max_package_size,
+                MAX_UPLOAD_FILES,
+            )),
+        )
         .route("/:id/versions/latest", get(ver::latest_handler))
         .route("/:id/versions/:version", get(ver::info_handler))
         .route("/:id/versions/:version", patch(ver::update_handler))
@@ -32,8 +44,22 @@ pub fn router(state: AppState) -> Router<AppState> {
             "/:id/versions/:version/download",
             get(ver::download_handler),
         )
+        .route("/:id/versions/:version/files", get(ver::files_handler))
+        .route(
+            "/:id/versions/:version/dependencies",
+            get(ver::dependencies_handler),
+        )
+        .route(
+            "/:id/versions/:version/dependents",
+            get(ver::dependents_handler),
+        )
+        .route("/:id/stats/downloads", get(stats::downloads_handler))
         .route("/:id/gallery", get(gallery::list_handler))
-        .route("/:id/gallery", put(gallery::upload_handler))
+        .route(
+            "/:id/gallery",
+            put(gallery::upload_handler)
+                .layer(upload_body_limit(state.config.server.max_image_size, 1)),
+        )
         .route("/:id/gallery/:image", get(gallery::info_handler))
         .route("/:id/gallery/:image", patch(gallery::update_handler))
         .route("/:id/gallery/:image", delete(gallery::delete_handler))
@@ -5,11 +5,34 @@ use axum::{
     Json,
 };
 use axum_extra::extract::CookieJar;
-use db::PackageVisibility;
+use db::{get_user_orgs, PackageVisibility, TokenScope, User};
 use search::{Facet, SearchResults, Sort, SortMode};
 
 pub const MAX_PER_PAGE: usize = 100;
 
+/// Get the facet that limits search results to the packages a user can see,
+/// given the organizations they're in. Admins can see everything.
+fn visibility_facet(user: Option<&User>, orgs: Vec<i32>) -> Option<Facet> {
+    let Some(user) = user else {
+        return Some(Facet::Visibility(PackageVisibility::Public));
+    };
+
+    if user.admin {
+        return None;
+    }
+
+    let mut visible = vec![
+        Facet::Visibility(PackageVisibility::Public).into_filter_string(),
+        Facet::Author(user.id).into_filter_string(),
+    ];
+
+    if !orgs.is_empty() {
+        visible.push(Facet::Organizations(orgs).into_filter_string());
+    }
+
+    Some(Facet::Manual(visible.join(" OR ")))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
 pub struct SearchQuery {
     /// The query string.
@@ -70,19 +93,21 @@ pub async fn search_handler(
         serde_json::from_str::<Vec<(String, Vec<String>)>>(&filters.unwrap_or("[]".into()))?;
     let mut facets = Vec::new();
 
-    match get_user_from_req(&jar, &headers, &mut conn).await {
-        Ok(user) => {
-            if !user.admin {
-                facets.push(Facet::Manual(format!(
-                    "{} OR {}",
-                    Facet::Visibility(PackageVisibility::Public).into_filter_string(),
-                    Facet::Author(user.id).into_filter_string()
-                )))
-            }
-        }
+    let user = get_user_from_req(&jar, &headers, &mut conn, TokenScope::Read, None)
+        .await
+        .ok();
 
-        Err(_) => facets.push(Facet::Visibility(PackageVisibility::Public)),
-    }
+    let orgs = match &user {
+        Some(user) if !user.admin => get_user_orgs(user.id, &mut conn)
+            .await?
+            .into_iter()
+            .map(|(member, _)| member.organization)
+            .collect(),
+
+        _ => Vec::new(),
+    };
+
+    facets.extend(visibility_facet(user.as_ref(), orgs));
 
     for item in filters {
         facets.push(Facet::parse(item)?);
@@ -107,3 +132,74 @@ pub async fn search_handler(
             .await?,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+    use db::{ApiToken, VersionChannel};
+
+    fn token(package: Option<i32>) -> ApiToken {
+        ApiToken {
+            id: 1,
+            user_id: 1,
+            name: "token".into(),
+            hash: String::new(),
+            prefix: String::new(),
+            scopes: vec![TokenScope::Read],
+            package,
+            expires: None,
+            created_at: NaiveDateTime::default(),
+            last_used: None,
+            last_ip: None,
+        }
+    }
+
+    /// The user a search is made as, if the token is allowed to search.
+    fn searcher(token: &ApiToken) -> Option<User> {
+        token.allows(TokenScope::Read, None).then(|| User {
+            id: token.user_id,
+            username: "someone".into(),
+            github_id: -1,
+            admin: false,
+        })
+    }
+
+    #[test]
+    fn restricted_tokens_only_find_public_packages() {
+        // The token is for package 1, but its owner can also see private package 2.
+        let restricted = token(Some(1));
+
+        assert!(restricted.allows(TokenScope::Read, Some(1)));
+        assert!(!restricted.allows(TokenScope::Read, Some(2)));
+
+        let facet = visibility_facet(searcher(&restricted).as_ref(), vec![3]);
+
+        assert_eq!(facet, Some(Facet::Visibility(PackageVisibility::Public)));
+    }
+
+    #[test]
+    fn unrestricted_tokens_find_the_users_packages() {
+        let facet = visibility_facet(searcher(&token(None)).as_ref(), vec![3]);
+
+        let Some(Facet::Manual(filter)) = facet else {
+            panic!("Expected a manual facet, got {:?}", facet);
+        };
+
+        assert!(filter.contains(&Facet::Author(1).into_filter_string()));
+        assert!(filter.contains(&Facet::Organizations(vec![3]).into_filter_string()));
+    }
+
+    #[test]
+    fn channel_facets_match_indexed_channels() {
+        let facet =
+            Facet::parse(("channels".into(), vec!["Release".into(), "beta".into()])).unwrap();
+
+        assert_eq!(facet.into_filter_string(), "(channels IN [release, beta])");
+
+        assert_eq!(
+            serde_json::to_value([VersionChannel::Release, VersionChannel::Beta]).unwrap(),
+            serde_json::json!(["release", "beta"])
+        );
+    }
+}
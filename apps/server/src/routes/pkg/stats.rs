@@ -0,0 +1,93 @@
+use crate::{
+    auth::package::ReadablePackage,
+    state::AppState,
+    util::stats::{aggregate_downloads, DownloadStats, Granularity},
+    Result,
+};
+use app_core::AppError;
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    response::Response,
+};
+use chrono::{Days, NaiveDate, Utc};
+use db::{get_daily_downloads, package_versions, PackageVersion};
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+/// The most periods that can be requested at once.
+pub const MAX_PERIODS: u64 = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, ToResponse)]
+pub struct DownloadStatsQuery {
+    /// The first day to include. Defaults to 30 days before `to`.
+    pub from: Option<NaiveDate>,
+
+    /// The last day to include. Defaults to today.
+    pub to: Option<NaiveDate>,
+
+    /// The size of each period. Defaults to `day`.
+    pub granularity: Option<Granularity>,
+}
+
+/// Get Download Statistics
+///
+/// Get a package's downloads over time, totalled per version, loader and game version.
+#[utoipa::path(
+    get,
+    path = "/api/v1/packages/{id}/stats/downloads",
+    tag = "Packages",
+    params(
+        ("id" = String, Path, description = "The package ID or slug."),
+        ("from" = Option<NaiveDate>, Query, description = "The first day to include (YYYY-MM-DD) - defaults to 30 days before `to`"),
+        ("to" = Option<NaiveDate>, Query, description = "The last day to include (YYYY-MM-DD) - defaults to today"),
+        ("granularity" = Option<Granularity>, Query, description = "The size of each period - defaults to `day`"),
+    ),
+    responses(
+        (status = 200, description = "Got download statistics!", body = DownloadStats),
+        (status = BAD_REQUEST, description = "The requested range is invalid or too large!"),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+)]
+#[debug_handler]
+pub async fn downloads_handler(
+    ReadablePackage { pkg, .. }: ReadablePackage,
+    State(state): State<AppState>,
+    Query(DownloadStatsQuery {
+        from,
+        to,
+        granularity,
+    }): Query<DownloadStatsQuery>,
+) -> Result<Response> {
+    let mut conn = state.pool.get().await?;
+    let granularity = granularity.unwrap_or_default();
+    let to = to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = from.unwrap_or_else(|| to - Days::new(30));
+
+    if from > to {
+        return Err(AppError::BadRequest(
+            "'from' must not be after 'to'!".to_string(),
+        ));
+    }
+
+    if granularity.count(from, to) > MAX_PERIODS {
+        return Err(AppError::BadRequest(format!(
+            "Too many periods requested (the limit is {})!",
+            MAX_PERIODS
+        )));
+    }
+
+    let rows = get_daily_downloads(pkg.id, from, to, &mut conn).await?;
+
+    let versions = package_versions::table
+        .filter(package_versions::package.eq(pkg.id))
+        .select(PackageVersion::as_select())
+        .load(&mut conn)
+        .await?;
+
+    let stats = aggregate_downloads(pkg.slug, &rows, &versions, from, to, granularity);
+
+    Ok(Response::builder()
+        .header("Content-Type", "application/json")
+        .body(Body::new(serde_json::to_string(&stats)?))?)
+}
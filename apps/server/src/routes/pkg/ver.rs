@@ -1,24 +1,42 @@
-use crate::{auth::get_user_from_req, state::AppState, util::versions::get_latest_version, Result};
-use anyhow::anyhow;
+use crate::{
+    auth::package::{perm, ReadablePackage, WritablePackage},
+    routes::pkg::search::MAX_PER_PAGE,
+    state::AppState,
+    util::{
+        download::stream_file,
+        relations::{get_version_dependencies, resolve_manifest_relations, ManifestRelations},
+        tracker::{client_ip, is_bot},
+        upload::{receive_file, receive_text, MAX_UPLOAD_FILES},
+        versions::{
+            get_latest_version, matches_range, parse_range, sort_versions, validate_version,
+        },
+    },
+    verify::ValidationReport,
+    Result,
+};
+use app_config::DownloadMode;
 use app_core::AppError;
 use axum::{
     body::Body,
-    extract::{Multipart, Path, State},
-    http::{HeaderMap, StatusCode},
+    extract::{ConnectInfo, Multipart, Path, Query, State},
+    http::{header::USER_AGENT, HeaderMap, StatusCode},
     response::Response,
     Json,
 };
-use axum_extra::extract::CookieJar;
 use chrono::Utc;
 use db::{
-    get_full_package, get_package, get_version, package_authors, package_versions, packages,
-    NewPackageVersion, Package, PackageAuthor, PackageVersion, PackageVersionInit,
-    PackageVisibility,
+    add_relations, add_requirements, get_dependents, get_user_orgs, get_version, get_version_file,
+    get_version_files, is_file_used, package_authors, package_versions, packages, record_download,
+    version_files, DbConn, NewPackageVersion, NewVersionFile, PackageAuthor, PackageVersion,
+    PackageVersionInit, PackageVisibility, User, VersionChannel, VersionFile, VersionRelation,
+};
+use diesel::{
+    delete, insert_into, update, BoolExpressionMethods, ExpressionMethods,
+    PgArrayExpressionMethods, QueryDsl, SelectableHelper,
 };
-use diesel::{delete, insert_into, update, ExpressionMethods, QueryDsl, SelectableHelper};
-use diesel_async::RunQueryDsl;
-use semver::Version;
-use sha1::{Digest, Sha1};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+use search::SortMode;
+use std::net::SocketAddr;
 
 #[derive(
     Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, ToSchema, ToResponse, Serialize, Deserialize,
@@ -38,50 +56,208 @@ pub struct PartialPackageVersion {
 
     #[serde(default)]
     pub game_versions: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub channel: Option<VersionChannel>,
+
+    /// Whether the version is yanked.
+    #[serde(default)]
+    pub yanked: Option<bool>,
+
+    /// A deprecation message for the version. An empty message removes the deprecation.
+    #[serde(default)]
+    pub deprecation: Option<String>,
+}
+
+/// A newly uploaded package version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, ToResponse)]
+pub struct UploadedVersion {
+    /// The version.
+    #[serde(flatten)]
+    pub version: PackageVersion,
+
+    /// Problems the verifier found that didn't stop the version from being uploaded.
+    pub warnings: Vec<String>,
+}
+
+/// How to sort a list of versions.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    ToSchema,
+    ToResponse,
+    Serialize,
+    Deserialize,
+    Default,
+)]
+pub enum VersionSort {
+    #[serde(rename = "version")]
+    #[default]
+    Version,
+
+    #[serde(rename = "published")]
+    Published,
+
+    #[serde(rename = "updated")]
+    Updated,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, ToSchema, ToResponse, Serialize, Deserialize,
+)]
+pub struct VersionListQuery {
+    /// Whether to include yanked versions.
+    #[serde(default)]
+    pub include_yanked: bool,
+
+    /// A version range to filter by, like `^1.2`.
+    #[serde(default)]
+    pub range: Option<String>,
+
+    /// Only include versions that work on this loader.
+    #[serde(default)]
+    pub loader: Option<String>,
+
+    /// Only include versions that work on this game version.
+    #[serde(default)]
+    pub game_version: Option<String>,
+
+    /// Only include versions on this release channel (or a more stable one).
+    #[serde(default)]
+    pub channel: Option<VersionChannel>,
+
+    /// The current page. If neither this nor `per_page` is set, every version is returned.
+    #[serde(default)]
+    pub page: Option<usize>,
+
+    /// How many versions per page. Defaults to 25.
+    #[serde(default)]
+    pub per_page: Option<usize>,
+
+    /// The sort mode. Defaults to sorting by version number.
+    #[serde(default)]
+    pub sort: Option<VersionSort>,
+
+    /// The sort direction. Defaults to ascending.
+    #[serde(default)]
+    pub dir: Option<SortMode>,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, ToSchema, ToResponse, Serialize, Deserialize,
+)]
+pub struct DownloadQuery {
+    /// The ID or name of the file to download. Defaults to the primary file.
+    #[serde(default)]
+    pub file: Option<String>,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, ToSchema, ToResponse, Serialize, Deserialize,
+)]
+pub struct LatestVersionQuery {
+    /// The release channel to get the latest version for.
+    #[serde(default)]
+    pub channel: Option<VersionChannel>,
 }
 
 /// List Package Versions
 ///
-/// List available versions for a specific package.
+/// List available versions for a specific package, optionally filtered, sorted, and paginated.
+/// Yanked versions are left out unless asked for.
 #[utoipa::path(
     get,
     path = "/api/v1/packages/{id}/versions",
     tag = "Versions",
     responses(
         (status = 200, description = "Found package versions!", body = Vec<PackageVersion>),
+        (status = BAD_REQUEST, description = "The version range is invalid!"),
         (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
     ),
     params(
         ("id" = String, Path, description = "The package ID whose versions we are looking for."),
+        ("include_yanked" = Option<bool>, Query, description = "Whether to include yanked versions."),
+        ("range" = Option<String>, Query, description = "A version range to filter by, like `^1.2`."),
+        ("loader" = Option<String>, Query, description = "Only include versions that work on this loader."),
+        ("game_version" = Option<String>, Query, description = "Only include versions that work on this game version."),
+        ("channel" = Option<VersionChannel>, Query, description = "Only include versions on this release channel (or a more stable one)."),
+        ("page" = Option<usize>, Query, description = "The current page (starting at 1). If neither this nor `per_page` is set, every version is returned."),
+        ("per_page" = Option<usize>, Query, description = "How many versions per page - defaults to 25"),
+        ("sort" = Option<VersionSort>, Query, description = "The sort mode - defaults to `version`"),
+        ("dir" = Option<SortMode>, Query, description = "The sort direction - defaults to `asc`"),
     ),
 )]
 #[debug_handler]
 pub async fn list_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(id): Path<String>,
+    ReadablePackage { pkg, .. }: ReadablePackage,
+    Query(query): Query<VersionListQuery>,
     State(state): State<AppState>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let pkg = get_full_package(id, &mut conn).await?;
+    let range = query
+        .range
+        .map(parse_range)
+        .transpose()
+        .map_err(AppError::BadRequest)?;
 
-    if pkg.visibility == PackageVisibility::Private {
-        match get_user_from_req(&jar, &headers, &mut conn).await {
-            Ok(user) => {
-                if !pkg.authors.iter().any(|v| v.github_id == user.github_id) && !user.admin {
-                    return Err(AppError::NotFound);
-                }
-            }
+    let mut versions = package_versions::table
+        .filter(package_versions::package.eq(pkg.id))
+        .into_boxed();
 
-            Err(_) => return Err(AppError::NotFound),
-        }
+    if !query.include_yanked {
+        versions = versions.filter(package_versions::yanked.eq(false));
     }
 
-    let versions = package_versions::table
-        .filter(package_versions::package.eq(pkg.id))
+    if let Some(loader) = query.loader {
+        versions = versions.filter(package_versions::loaders.contains(vec![Some(loader)]));
+    }
+
+    if let Some(game_version) = query.game_version {
+        versions =
+            versions.filter(package_versions::game_versions.contains(vec![Some(game_version)]));
+    }
+
+    let mut versions = versions
         .select(PackageVersion::as_select())
         .load(&mut conn)
-        .await?;
+        .await?
+        .into_iter()
+        .filter(|v| match &range {
+            Some(range) => matches_range(range, &v.version_number),
+            None => true,
+        })
+        .filter(|v| match query.channel {
+            Some(channel) => channel.includes(v.channel),
+            None => true,
+        })
+        .collect::<Vec<_>>();
+
+    match query.sort.unwrap_or_default() {
+        VersionSort::Version => sort_versions(&mut versions),
+        VersionSort::Published => versions.sort_by_key(|v| v.created_at),
+        VersionSort::Updated => versions.sort_by_key(|v| v.updated_at),
+    }
+
+    if query.dir.unwrap_or_default() == SortMode::Descending {
+        versions.reverse();
+    }
+
+    if query.page.is_some() || query.per_page.is_some() {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(25).clamp(1, MAX_PER_PAGE);
+
+        versions = versions
+            .into_iter()
+            .skip((page - 1).saturating_mul(per_page))
+            .take(per_page)
+            .collect();
+    }
 
     Ok(Response::builder()
         .header("Content-Type", "application/json")
@@ -106,26 +282,11 @@ pub async fn list_handler(
 )]
 #[debug_handler]
 pub async fn info_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path((package, version)): Path<(String, String)>,
+    Path((_id, version)): Path<(String, String)>,
+    ReadablePackage { pkg, .. }: ReadablePackage,
     State(state): State<AppState>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let pkg = get_full_package(package, &mut conn).await?;
-
-    if pkg.visibility == PackageVisibility::Private {
-        match get_user_from_req(&jar, &headers, &mut conn).await {
-            Ok(user) => {
-                if !pkg.authors.iter().any(|v| v.github_id == user.github_id) && !user.admin {
-                    return Err(AppError::NotFound);
-                }
-            }
-
-            Err(_) => return Err(AppError::NotFound),
-        }
-    }
-
     let ver = get_version(pkg.id, version, &mut conn).await?;
 
     Ok(Response::builder()
@@ -135,42 +296,31 @@ pub async fn info_handler(
 
 /// Get Latest Package Version
 ///
-/// Get information about the latest package version
+/// Get information about the latest package version.
+/// If a channel is given, only versions on that channel (or a more stable one) are considered.
+/// Otherwise, this is the latest release (or the latest version if there are no releases).
 #[utoipa::path(
     get,
     path = "/api/v1/packages/{id}/versions/latest",
     tag = "Versions",
     responses(
         (status = 200, description = "Found latest version!", body = PackageVersion),
+        (status = NOT_FOUND, description = "The package has no versions on that channel!"),
         (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
     ),
     params(
         ("id" = String, Path, description = "The package that this version is for."),
+        ("channel" = Option<VersionChannel>, Query, description = "The release channel - defaults to the latest release"),
     ),
 )]
 #[debug_handler]
 pub async fn latest_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(package): Path<String>,
+    ReadablePackage { pkg, .. }: ReadablePackage,
     State(state): State<AppState>,
+    Query(LatestVersionQuery { channel }): Query<LatestVersionQuery>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let pkg = get_full_package(package, &mut conn).await?;
-
-    if pkg.visibility == PackageVisibility::Private {
-        match get_user_from_req(&jar, &headers, &mut conn).await {
-            Ok(user) => {
-                if !pkg.authors.iter().any(|v| v.github_id == user.github_id) && !user.admin {
-                    return Err(AppError::NotFound);
-                }
-            }
-
-            Err(_) => return Err(AppError::NotFound),
-        }
-    }
-
-    let ver = get_latest_version(pkg.id, &mut conn).await?;
+    let ver = get_latest_version(pkg.id, channel, &mut conn).await?;
 
     Ok(Response::builder()
         .header("Content-Type", "application/json")
@@ -179,86 +329,134 @@ pub async fn latest_handler(
 
 /// Download Package Version
 ///
-/// Download a specific package version
+/// Download a specific package version's primary file, or one of its other files.
+/// Depending on the server's configuration, this either redirects to a
+/// short-lived URL or streams the file (with support for range requests).
+/// Only downloads of the primary file are counted.
 #[utoipa::path(
     get,
     path = "/api/v1/packages/{id}/versions/{version}/download",
     tag = "Versions",
     responses(
+        (status = 200, description = "The package file", content_type = "application/octet-stream"),
+        (status = 206, description = "Part of the package file", content_type = "application/octet-stream"),
+        (status = 304, description = "The package file has not changed"),
         (status = 307, description = "Redirecting to download"),
+        (status = 416, description = "The requested range is outside of the file"),
         (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
     ),
     params(
         ("id" = String, Path, description = "The package that this version is for."),
         ("version" = String, Path, description = "The version ID/name/number."),
+        ("file" = Option<String>, Query, description = "The ID or name of the file to download. Defaults to the primary file."),
     ),
 )]
 #[debug_handler]
 pub async fn download_handler(
-    jar: CookieJar,
     headers: HeaderMap,
-    Path((package, version)): Path<(String, String)>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Path((_id, version)): Path<(String, String)>,
+    ReadablePackage { pkg, user }: ReadablePackage,
+    Query(query): Query<DownloadQuery>,
     State(state): State<AppState>,
-) -> Result<Vec<u8>> {
+) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let pkg = get_full_package(package, &mut conn).await?;
+    let ver = get_version(pkg.id, version, &mut conn).await?;
+    let file = get_version_file(ver.id, query.file, &mut conn).await?;
+
+    let storage = state.buckets.packages.as_ref();
+
+    let ext = state.config.ui.package_file_formats[..]
+        .first()
+        .map(|v| v.as_str())
+        .unwrap_or_default();
+
+    let file_name = file
+        .file_name
+        .clone()
+        .unwrap_or_else(|| format!("{}-{}{}", pkg.slug, ver.version_number, ext));
+
+    let url = match state.config.storage.download_mode {
+        DownloadMode::Redirect => {
+            storage
+                .presign(
+                    &file.file_id,
+                    state.config.storage.presign_expiry,
+                    &file_name,
+                )
+                .await?
+        }
 
-    if pkg.visibility == PackageVisibility::Private {
-        match get_user_from_req(&jar, &headers, &mut conn).await {
-            Ok(user) => {
-                if !pkg.authors.iter().any(|v| v.github_id == user.github_id) && !user.admin {
-                    return Err(AppError::NotFound);
-                }
-            }
+        DownloadMode::Stream => None,
+    };
 
-            Err(_) => return Err(AppError::NotFound),
-        }
-    }
+    let res = match url {
+        Some(url) => Response::builder()
+            .status(StatusCode::TEMPORARY_REDIRECT)
+            .header("Location", url)
+            .body(Body::empty())?,
 
-    let ver = get_version(pkg.id, version, &mut conn).await?;
+        None => {
+            let size = match file.size {
+                Some(size) => size as u64,
+                None => storage.size(&file.file_id).await?,
+            };
 
-    update(packages::table)
-        .filter(packages::id.eq(pkg.id))
-        .set((
-            packages::downloads.eq(pkg.downloads + 1),
-            packages::updated_at.eq(pkg.updated_at),
-        ))
-        .returning(Package::as_returning())
-        .get_result(&mut conn)
-        .await?;
+            stream_file(storage, &file.file_id, size, &file_name, &headers).await?
+        }
+    };
 
-    let ver = update(package_versions::table)
-        .filter(package_versions::id.eq(ver.id))
-        .set((
-            package_versions::downloads.eq(ver.downloads + 1),
-            package_versions::updated_at.eq(ver.updated_at),
-        ))
-        .returning(PackageVersion::as_returning())
-        .get_result(&mut conn)
-        .await?;
+    // Only whole downloads of the primary file are counted, not range
+    // requests or cache revalidations. Redirects count too, since the file
+    // itself is then downloaded straight from storage.
+    let is_download = matches!(
+        res.status(),
+        StatusCode::OK | StatusCode::TEMPORARY_REDIRECT
+    );
+
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    if !is_download || !file.is_primary || is_bot(user_agent) {
+        return Ok(res);
+    }
 
-    state.search.update_package(pkg.id, &mut conn).await?;
+    // Signed-in users are told apart by their account, and anyone else by
+    // their IP, so made-up tokens can't be used to inflate download counts.
+    let client = match user {
+        Some(user) => format!("user:{}", user.id),
+        None => format!(
+            "ip:{}",
+            client_ip(&headers, addr, state.config.server.behind_proxy)
+        ),
+    };
 
-    let bytes = state
-        .buckets
-        .packages
-        .get_object(format!("/{}.tgz", ver.file_id))
-        .await?
-        .into_bytes()
-        .to_vec();
+    if state.downloads.should_count(client, ver.id) {
+        record_download(pkg.id, ver.id, &mut conn).await?;
+        state.downloads.mark_dirty(pkg.id);
+    }
 
-    Ok(bytes)
+    Ok(res)
 }
 
 /// Upload Package Version
 ///
-/// Upload a new package version
+/// Upload a new package version.
+/// Any metadata left out is filled in from the package's manifest.
+/// The first `file` part is the version's primary file, and any others are
+/// stored as extra files (like sources or a server-only variant).
+/// Any warnings from checking the package are returned with the new version.
 #[utoipa::path(
     put,
     path = "/api/v1/packages/{id}/versions",
     tag = "Versions",
     responses(
-        (status = 200, description = "Created package version!", body = PackageVersion),
+        (status = 200, description = "Created package version!", body = UploadedVersion),
+        (status = BAD_REQUEST, description = "A field conflicts with the package manifest, the version number or channel is invalid, two files have the same name, or one of the manifest's dependencies could not be resolved!"),
+        (status = PAYLOAD_TOO_LARGE, description = "A file or field is over the server's size limit, or there are too many files!"),
+        (status = UNPROCESSABLE_ENTITY, description = "The package file failed verification!", body = ValidationReport),
         (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
     ),
     params(
@@ -271,143 +469,390 @@ pub async fn download_handler(
 )]
 #[debug_handler]
 pub async fn create_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path(id): Path<String>,
+    WritablePackage { pkg, user, .. }: WritablePackage<perm::Upload>,
     State(state): State<AppState>,
     mut data: Multipart,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
-    let pkg = get_package(id, &mut conn).await?;
-
-    let authors = package_authors::table
-        .filter(package_authors::package.eq(pkg.id))
-        .select(PackageAuthor::as_select())
-        .load(&mut conn)
-        .await?;
-
-    if authors.iter().find(|v| v.user_id == user.id).is_none() && !user.admin {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::empty())?);
-    }
 
     let mut name = None;
     let mut version_number = None;
     let mut changelog = None;
     let mut loaders = None;
     let mut game_versions = None;
-    let mut file = None;
-
-    while let Ok(Some(field)) = data.next_field().await {
-        match field
-            .name()
-            .ok_or(anyhow!("Could not find a name for a field!"))?
-        {
-            "name" => name = Some(field.text().await?),
-            "version_number" => version_number = Some(field.text().await?),
-            "changelog" => changelog = Some(field.text().await?),
+    let mut channel = None;
+    let mut files = Vec::new();
+
+    while let Some(field) = data.next_field().await? {
+        match field.name().ok_or(AppError::BadRequest(
+            "Could not find a name for a field!".into(),
+        ))? {
+            "name" => name = Some(receive_text(field).await?),
+            "version_number" => version_number = Some(receive_text(field).await?),
+            "changelog" => changelog = Some(receive_text(field).await?),
             "loaders" => {
                 loaders = Some(
-                    field
-                        .text()
+                    receive_text(field)
                         .await?
                         .split(",")
-                        .map(|v| Some(v.to_string()))
+                        .map(|v| v.trim().to_string())
                         .collect::<Vec<_>>(),
                 )
             }
             "game_versions" => {
                 game_versions = Some(
-                    field
-                        .text()
+                    receive_text(field)
                         .await?
                         .split(",")
-                        .map(|v| Some(v.to_string()))
+                        .map(|v| v.trim().to_string())
                         .collect::<Vec<_>>(),
                 )
             }
-            "file" => file = Some(field.bytes().await?),
+            "channel" => channel = Some(receive_text(field).await?.parse::<VersionChannel>()?),
+            "file" => {
+                if files.len() >= MAX_UPLOAD_FILES {
+                    return Err(AppError::TooManyFiles(MAX_UPLOAD_FILES));
+                }
+
+                let mut file = receive_file(field, state.config.server.max_package_size).await?;
+
+                file.file_name = file.file_name.as_deref().and_then(clean_file_name);
+                files.push(file);
+            }
             _ => {}
         }
     }
 
-    if name.is_none() {
-        Err(anyhow!("Missing field: 'name'"))?;
+    if files.is_empty() {
+        return Err(AppError::BadRequest("Missing field: 'file'".into()));
+    }
+
+    let names = files
+        .iter()
+        .filter_map(|v| v.file_name.as_ref())
+        .collect::<Vec<_>>();
+
+    if let Some(dup) = names
+        .iter()
+        .enumerate()
+        .find(|(i, name)| names[..*i].contains(name))
+    {
+        return Err(AppError::BadRequest(format!(
+            "Duplicate file name: '{}'",
+            dup.1
+        )));
     }
 
+    let verifier = state.verifier.clone();
+    let path = files[0].path().to_path_buf();
+    let report = tokio::task::spawn_blocking(move || verifier.verify(&path)).await?;
+
+    if !report.is_valid() {
+        return Err(AppError::InvalidPackage(serde_json::to_value(&report)?));
+    }
+
+    let manifest = report.manifest.as_ref();
+
+    let version_number = merge_field(
+        "version_number",
+        version_number,
+        manifest.map(|v| v.version.clone()),
+    )
+    .map_err(AppError::BadRequest)?;
+
+    let loaders = merge_field(
+        "loaders",
+        loaders.map(normalize_list),
+        manifest
+            .map(|v| normalize_list(v.loaders.clone()))
+            .filter(|v| !v.is_empty()),
+    )
+    .map_err(AppError::BadRequest)?;
+
+    let game_versions = merge_field(
+        "game_versions",
+        game_versions.map(normalize_list),
+        manifest
+            .map(|v| normalize_list(v.game_versions.clone()))
+            .filter(|v| !v.is_empty()),
+    )
+    .map_err(AppError::BadRequest)?;
+
     if version_number.is_none() {
-        Err(anyhow!("Missing field: 'version_number'"))?;
+        return Err(AppError::BadRequest(
+            "Missing field: 'version_number'".into(),
+        ));
     }
 
     if loaders.is_none() {
-        Err(anyhow!("Missing field: 'loaders'"))?;
+        return Err(AppError::BadRequest("Missing field: 'loaders'".into()));
     }
 
     if game_versions.is_none() {
-        Err(anyhow!("Missing field: 'game_versions'"))?;
-    }
-
-    if file.is_none() {
-        Err(anyhow!("Missing field: 'file'"))?;
+        return Err(AppError::BadRequest(
+            "Missing field: 'game_versions'".into(),
+        ));
     }
 
-    let name = name.unwrap();
     let version_number = version_number.unwrap();
-    let loaders = loaders.unwrap();
-    let game_versions = game_versions.unwrap();
-    let file = file.unwrap();
+    let name = name.unwrap_or_else(|| version_number.clone());
+    let loaders = loaders.unwrap().into_iter().map(Some).collect::<Vec<_>>();
 
-    Version::parse(&version_number)?;
+    let game_versions = game_versions
+        .unwrap()
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<_>>();
 
-    if !(state.verifier)(file.clone()) {
-        Err(anyhow!("Invalid package!"))?;
-    }
-
-    let mut hasher = Sha1::new();
+    validate_version(&version_number).map_err(AppError::BadRequest)?;
 
-    hasher.update(&file);
+    let relations = match &report.manifest {
+        Some(manifest) => {
+            resolve_manifest_relations(manifest, &loaders, &game_versions, &user, &mut conn).await?
+        }
 
-    let file_id = format!("{:x}", hasher.finalize());
-    let file_name = format!("{}", file_id);
+        None => ManifestRelations::default(),
+    };
 
-    state
-        .buckets
-        .packages
-        .put_object(format!("/{}", file_name), &file)
-        .await?;
+    let primary = &files[0].hashes;
 
     let data = NewPackageVersion {
         package: pkg.id,
         name,
         version_number,
-        file_id,
+        file_id: primary.file_id(),
         changelog,
         loaders,
         game_versions,
         downloads: 0,
+        sha1: primary.sha1.clone(),
+        sha256: Some(primary.sha256.clone()),
+        sha512: Some(primary.sha512.clone()),
+        file_size: Some(primary.size),
+        channel: channel.unwrap_or_default(),
     };
 
-    update(packages::table)
-        .filter(packages::id.eq(pkg.id))
-        .set(packages::updated_at.eq(Utc::now().naive_utc()))
-        .returning(Package::as_returning())
-        .get_result(&mut conn)
+    let mut stored = Vec::new();
+
+    let res = async {
+        let files = &files;
+
+        for file in files {
+            let file_id = file.hashes.file_id();
+
+            state
+                .buckets
+                .packages
+                .put_file(&file_id, file.path())
+                .await?;
+
+            stored.push(file_id);
+        }
+
+        conn.transaction::<_, AppError, _>(|conn| {
+            async move {
+                update(packages::table)
+                    .filter(packages::id.eq(pkg.id))
+                    .set(packages::updated_at.eq(Utc::now().naive_utc()))
+                    .execute(conn)
+                    .await?;
+
+                let ver = insert_into(package_versions::table)
+                    .values(&data)
+                    .returning(PackageVersion::as_returning())
+                    .get_result(conn)
+                    .await?;
+
+                let ver_files = files
+                    .iter()
+                    .enumerate()
+                    .map(|(i, file)| NewVersionFile {
+                        version: ver.id,
+                        file_id: file.hashes.file_id(),
+                        file_name: file.file_name.clone(),
+                        size: Some(file.hashes.size),
+                        sha1: file.hashes.sha1.clone(),
+                        sha256: Some(file.hashes.sha256.clone()),
+                        sha512: Some(file.hashes.sha512.clone()),
+                        is_primary: i == 0,
+                    })
+                    .collect::<Vec<_>>();
+
+                insert_into(version_files::table)
+                    .values(&ver_files)
+                    .execute(conn)
+                    .await?;
+
+                add_relations(ver.id, relations.relations, conn).await?;
+                add_requirements(ver.id, relations.requirements, conn).await?;
+
+                Ok(ver)
+            }
+            .scope_boxed()
+        })
         .await
-        .unwrap();
+    }
+    .await;
+
+    // If anything failed, nothing was written to the database, so any files
+    // we stored that no other version uses are only taking up space.
+    let ver = match res {
+        Ok(ver) => ver,
+
+        Err(err) => {
+            for file_id in stored {
+                if let Ok(false) = is_file_used(&file_id, &mut conn).await {
+                    if let Err(err) = state.buckets.packages.delete(&file_id).await {
+                        warn!("Could not delete unused file {}: {}", file_id, err);
+                    }
+                }
+            }
 
-    let ver = insert_into(package_versions::table)
-        .values(&data)
-        .returning(PackageVersion::as_returning())
-        .get_result(&mut conn)
-        .await?;
+            return Err(err);
+        }
+    };
 
     state.search.update_package(pkg.id, &mut conn).await?;
 
+    let res = UploadedVersion {
+        version: ver,
+        warnings: report.warnings,
+    };
+
     Ok(Response::builder()
         .header("Content-Type", "application/json")
-        .body(Body::new(serde_json::to_string(&ver)?))?)
+        .body(Body::new(serde_json::to_string(&res)?))?)
+}
+
+/// Get Package Version Files
+///
+/// Get the files of a specific package version, with the primary file first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/packages/{id}/versions/{version}/files",
+    tag = "Versions",
+    responses(
+        (status = 200, description = "Found files!", body = Vec<VersionFile>),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    params(
+        ("id" = String, Path, description = "The package that this version is for."),
+        ("version" = String, Path, description = "The version ID/name/number."),
+    ),
+)]
+#[debug_handler]
+pub async fn files_handler(
+    Path((_id, version)): Path<(String, String)>,
+    ReadablePackage { pkg, .. }: ReadablePackage,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<VersionFile>>> {
+    let mut conn = state.pool.get().await?;
+    let ver = get_version(pkg.id, version, &mut conn).await?;
+
+    Ok(Json(get_version_files(ver.id, &mut conn).await?))
+}
+
+/// Get Package Version Dependencies
+///
+/// Get the package versions that a specific package version depends on or is incompatible with.
+#[utoipa::path(
+    get,
+    path = "/api/v1/packages/{id}/versions/{version}/dependencies",
+    tag = "Versions",
+    responses(
+        (status = 200, description = "Found dependencies!", body = Vec<VersionRelation>),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    params(
+        ("id" = String, Path, description = "The package that this version is for."),
+        ("version" = String, Path, description = "The version ID/name/number."),
+    ),
+)]
+#[debug_handler]
+pub async fn dependencies_handler(
+    Path((_id, version)): Path<(String, String)>,
+    ReadablePackage { pkg, user }: ReadablePackage,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<VersionRelation>>> {
+    let mut conn = state.pool.get().await?;
+    let ver = get_version(pkg.id, version, &mut conn).await?;
+    let deps = get_version_dependencies(&ver, &mut conn).await?;
+
+    Ok(Json(filter_visible(deps, user.as_ref(), &mut conn).await?))
+}
+
+/// Get Package Version Dependents
+///
+/// Get the package versions that depend on or are incompatible with a specific package version.
+#[utoipa::path(
+    get,
+    path = "/api/v1/packages/{id}/versions/{version}/dependents",
+    tag = "Versions",
+    responses(
+        (status = 200, description = "Found dependents!", body = Vec<VersionRelation>),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    params(
+        ("id" = String, Path, description = "The package that this version is for."),
+        ("version" = String, Path, description = "The version ID/name/number."),
+    ),
+)]
+#[debug_handler]
+pub async fn dependents_handler(
+    Path((_id, version)): Path<(String, String)>,
+    ReadablePackage { pkg, user }: ReadablePackage,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<VersionRelation>>> {
+    let mut conn = state.pool.get().await?;
+    let ver = get_version(pkg.id, version, &mut conn).await?;
+    let deps = get_dependents(ver.id, &mut conn).await?;
+
+    Ok(Json(filter_visible(deps, user.as_ref(), &mut conn).await?))
+}
+
+/// Remove relations to private packages that the user can't see.
+async fn filter_visible(
+    relations: Vec<VersionRelation>,
+    user: Option<&User>,
+    conn: &mut DbConn,
+) -> Result<Vec<VersionRelation>> {
+    if user.is_some_and(|v| v.admin) {
+        return Ok(relations);
+    }
+
+    let authored =
+        match user {
+            Some(user) => {
+                package_authors::table
+                    .filter(package_authors::user_id.eq(user.id).and(
+                        package_authors::package.eq_any(relations.iter().map(|v| v.package.id)),
+                    ))
+                    .select(PackageAuthor::as_select())
+                    .load(conn)
+                    .await?
+            }
+
+            None => Vec::new(),
+        };
+
+    let orgs = match user {
+        Some(user) => get_user_orgs(user.id, conn)
+            .await?
+            .into_iter()
+            .map(|(member, _)| member.organization)
+            .collect(),
+
+        None => Vec::new(),
+    };
+
+    Ok(relations
+        .into_iter()
+        .filter(|v| {
+            v.package.visibility != PackageVisibility::Private
+                || authored.iter().any(|a| a.package == v.package.id)
+                || v.package.organization.is_some_and(|it| orgs.contains(&it))
+        })
+        .collect())
 }
 
 /// Update Package Version
@@ -419,6 +864,7 @@ pub async fn create_handler(
     tag = "Versions",
     responses(
         (status = 200, description = "Updated package version!", body = PackageVersion),
+        (status = BAD_REQUEST, description = "The version number is invalid!"),
         (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
     ),
     params(
@@ -432,27 +878,16 @@ pub async fn create_handler(
 )]
 #[debug_handler]
 pub async fn update_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path((package, version)): Path<(String, String)>,
+    Path((_id, version)): Path<(String, String)>,
+    WritablePackage { pkg, .. }: WritablePackage<perm::Upload>,
     State(state): State<AppState>,
     Json(data): Json<PartialPackageVersion>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
-    let pkg = get_package(package, &mut conn).await?;
     let ver = get_version(pkg.id, version, &mut conn).await?;
 
-    let authors = package_authors::table
-        .filter(package_authors::package.eq(pkg.id))
-        .select(PackageAuthor::as_select())
-        .load(&mut conn)
-        .await?;
-
-    if authors.iter().find(|v| v.user_id == user.id).is_none() && !user.admin {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::empty())?);
+    if let Some(version_number) = &data.version_number {
+        validate_version(version_number).map_err(AppError::BadRequest)?;
     }
 
     let ver = update(package_versions::table)
@@ -470,6 +905,12 @@ pub async fn update_handler(
                 .game_versions
                 .map(|v| v.iter().map(|v| Some(v.clone())).collect::<Vec<_>>())
                 .unwrap_or(ver.game_versions)),
+            package_versions::channel.eq(data.channel.unwrap_or(ver.channel)),
+            package_versions::yanked.eq(data.yanked.unwrap_or(ver.yanked)),
+            package_versions::deprecation.eq(data
+                .deprecation
+                .map(|v| Some(v).filter(|v| !v.is_empty()))
+                .unwrap_or(ver.deprecation)),
             package_versions::updated_at.eq(Utc::now().naive_utc()),
         ))
         .returning(PackageVersion::as_select())
@@ -504,50 +945,89 @@ pub async fn update_handler(
 )]
 #[debug_handler]
 pub async fn delete_handler(
-    jar: CookieJar,
-    headers: HeaderMap,
-    Path((package, version)): Path<(String, String)>,
+    Path((_id, version)): Path<(String, String)>,
+    WritablePackage { pkg, .. }: WritablePackage<perm::Upload>,
     State(state): State<AppState>,
 ) -> Result<Response> {
     let mut conn = state.pool.get().await?;
-    let user = get_user_from_req(&jar, &headers, &mut conn).await?;
-    let pkg = get_package(package, &mut conn).await?;
     let ver = get_version(pkg.id, version, &mut conn).await?;
 
-    let authors = package_authors::table
-        .filter(package_authors::package.eq(pkg.id))
-        .select(PackageAuthor::as_select())
-        .load(&mut conn)
-        .await?;
-
-    if authors.iter().find(|v| v.user_id == user.id).is_none() && !user.admin {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::empty())?);
-    }
+    // The version is deleted before any of its files, so a failure part of
+    // the way through can only leave unused files behind, not a version
+    // pointing at missing ones.
+    let unused = conn
+        .transaction::<_, AppError, _>(|conn| {
+            async move {
+                let mut file_ids = get_version_files(ver.id, conn)
+                    .await?
+                    .into_iter()
+                    .map(|v| v.file_id)
+                    .collect::<Vec<_>>();
+
+                file_ids.sort();
+                file_ids.dedup();
+
+                delete(package_versions::table)
+                    .filter(package_versions::id.eq(ver.id))
+                    .execute(conn)
+                    .await?;
+
+                let mut unused = Vec::new();
+
+                for file_id in file_ids {
+                    if !is_file_used(&file_id, conn).await? {
+                        unused.push(file_id);
+                    }
+                }
 
-    let all_referencing = package_versions::table
-        .filter(package_versions::file_id.eq(ver.file_id.clone()))
-        .select(PackageVersion::as_select())
-        .load(&mut conn)
+                Ok(unused)
+            }
+            .scope_boxed()
+        })
         .await?;
 
-    if all_referencing.len() <= 1 {
-        state
-            .buckets
-            .packages
-            .delete_object(format!("/{}", ver.file_id))
-            .await?;
+    for file_id in unused {
+        state.buckets.packages.delete(&file_id).await?;
     }
 
-    delete(package_versions::table)
-        .filter(package_versions::id.eq(ver.id))
-        .execute(&mut conn)
-        .await?;
-
     state.search.update_package(pkg.id, &mut conn).await?;
 
     Ok(Response::builder().body(Body::new(
         "Deleted package version successfully!".to_string(),
     ))?)
 }
+
+/// Clean up an uploaded file's name, keeping only the last path component.
+fn clean_file_name(name: &str) -> Option<String> {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+
+    if name.is_empty() || name == "." || name == ".." {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// Fill in a version field from the package manifest.
+/// Fails if the field was given explicitly and doesn't match the manifest.
+fn merge_field<T: PartialEq>(
+    field: &str,
+    given: Option<T>,
+    manifest: Option<T>,
+) -> std::result::Result<Option<T>, String> {
+    match (given, manifest) {
+        (Some(given), Some(manifest)) if given != manifest => Err(format!(
+            "Field '{}' conflicts with the package manifest!",
+            field
+        )),
+        (given, manifest) => Ok(given.or(manifest)),
+    }
+}
+
+/// Sort and deduplicate a list so it can be compared regardless of order.
+fn normalize_list(mut list: Vec<String>) -> Vec<String> {
+    list.retain(|v| !v.is_empty());
+    list.sort();
+    list.dedup();
+    list
+}
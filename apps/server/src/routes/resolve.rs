@@ -0,0 +1,69 @@
+use crate::{
+    auth::get_user_from_req,
+    state::AppState,
+    util::resolve::{resolve_packages, ResolveResults},
+    Result,
+};
+use axum::{extract::State, http::HeaderMap, routing::post, Json, Router};
+use axum_extra::extract::CookieJar;
+use db::TokenScope;
+
+/// A request to resolve a set of packages.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct ResolveRequest {
+    /// The package IDs or slugs to resolve, optionally with a version
+    /// requirement (`package@requirement`).
+    pub packages: Vec<String>,
+
+    /// The loader the packages need to work on.
+    pub loader: String,
+
+    /// The game version the packages need to work on.
+    pub game_version: String,
+}
+
+/// Resolve Packages
+///
+/// Resolve a set of packages and their dependencies to the newest versions
+/// compatible with a loader and game version that match what was required of them.
+#[utoipa::path(
+    post,
+    path = "/api/v1/resolve",
+    tag = "Packages",
+    responses(
+        (status = 200, description = "Resolved packages!", body = ResolveResults),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    request_body(content = ResolveRequest, description = "The packages to resolve"),
+)]
+#[debug_handler]
+pub async fn resolve_handler(
+    jar: CookieJar,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    Json(body): Json<ResolveRequest>,
+) -> Result<Json<ResolveResults>> {
+    let mut conn = state.pool.get().await?;
+    let user = get_user_from_req(&jar, &headers, &mut conn, TokenScope::Read, None)
+        .await
+        .ok();
+
+    Ok(Json(
+        resolve_packages(
+            body.packages,
+            body.loader,
+            body.game_version,
+            user.as_ref(),
+            &mut conn,
+        )
+        .await?,
+    ))
+}
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", post(resolve_handler))
+        .with_state(state)
+}
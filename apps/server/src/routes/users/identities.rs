@@ -0,0 +1,101 @@
+use crate::{auth::get_session_user_from_req, state::AppState, Result};
+use app_core::AppError;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use db::{get_user_identities, user_identities, users, UserIdentity};
+use diesel::{delete, update, ExpressionMethods};
+use diesel_async::RunQueryDsl;
+
+/// List Identities
+///
+/// List the login provider accounts linked to the current user.
+/// To link another one, log in with it while already logged in.
+/// This needs a login token.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/identities",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Found identities!", body = Vec<UserIdentity>),
+        (status = 401, description = "Personal API tokens can't manage identities!"),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn list_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+) -> Result<Json<Vec<UserIdentity>>> {
+    let mut conn = state.pool.get().await?;
+    let (user, _) = get_session_user_from_req(&jar, &headers, &mut conn).await?;
+
+    Ok(Json(get_user_identities(user.id, &mut conn).await?))
+}
+
+/// Unlink Identity
+///
+/// Unlink a login provider account from the current user.
+/// The last identity can't be unlinked, since there would be no way to log in.
+/// This needs a login token.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/identities/{id}",
+    tag = "Users",
+    params(
+        ("id" = i32, Path, description = "The identity's ID"),
+    ),
+    responses(
+        (status = 200, description = "Unlinked identity!", body = String),
+        (status = 400, description = "That's the user's only identity!"),
+        (status = 404, description = "Identity not found!"),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+) -> Result<String> {
+    let mut conn = state.pool.get().await?;
+    let (user, _) = get_session_user_from_req(&jar, &headers, &mut conn).await?;
+    let identities = get_user_identities(user.id, &mut conn).await?;
+
+    let identity = identities
+        .iter()
+        .find(|it| it.id == id)
+        .ok_or(AppError::NotFound)?;
+
+    if identities.len() == 1 {
+        return Err(AppError::BadRequest(
+            "You can't unlink your only identity!".into(),
+        ));
+    }
+
+    delete(user_identities::table)
+        .filter(user_identities::id.eq(identity.id))
+        .execute(&mut conn)
+        .await?;
+
+    if identity.provider == "github" {
+        update(users::table)
+            .filter(users::id.eq(user.id))
+            .set(users::github_id.eq(-1))
+            .execute(&mut conn)
+            .await?;
+    }
+
+    Ok("Unlinked identity successfully!".into())
+}
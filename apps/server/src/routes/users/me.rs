@@ -1,7 +1,7 @@
 use crate::{auth::get_user_from_req, state::AppState, Result};
 use axum::{body::Body, extract::State, http::HeaderMap, response::Response};
 use axum_extra::extract::CookieJar;
-use db::User;
+use db::{TokenScope, User};
 
 /// Current User
 ///
@@ -26,7 +26,14 @@ pub async fn me_handler(
 ) -> Result<Response> {
     Ok(
         Response::builder().body(Body::new(serde_json::to_string_pretty(
-            &get_user_from_req(&jar, &headers, &mut state.pool.get().await?).await?,
+            &get_user_from_req(
+                &jar,
+                &headers,
+                &mut state.pool.get().await?,
+                TokenScope::Read,
+                None,
+            )
+            .await?,
         )?))?,
     )
 }
@@ -1,16 +1,33 @@
+pub mod identities;
 pub mod info;
 pub mod me;
+pub mod orgs;
 pub mod pkg;
 pub mod search;
+pub mod sessions;
+pub mod tokens;
 
 use crate::state::AppState;
-use axum::{routing::get, Router};
+use axum::{
+    routing::{delete, get},
+    Router,
+};
 
 pub fn router(state: AppState) -> Router<AppState> {
     Router::new()
         .route("/me", get(me::me_handler))
+        .route(
+            "/me/tokens",
+            get(tokens::list_handler).put(tokens::create_handler),
+        )
+        .route("/me/tokens/:id", delete(tokens::delete_handler))
+        .route("/me/sessions", get(sessions::list_handler))
+        .route("/me/sessions/:id", delete(sessions::revoke_handler))
+        .route("/me/identities", get(identities::list_handler))
+        .route("/me/identities/:id", delete(identities::delete_handler))
         .route("/search", get(search::search_handler))
         .route("/:id", get(info::info_handler))
         .route("/:id/packages", get(pkg::list_handler))
+        .route("/:id/orgs", get(orgs::list_handler))
         .with_state(state)
 }
@@ -0,0 +1,54 @@
+use crate::{state::AppState, Result};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use db::{get_user, get_user_orgs, OrgRole, Organization};
+
+/// An organization a user is in.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct UserOrganization {
+    /// The organization.
+    #[serde(flatten)]
+    pub org: Organization,
+
+    /// The user's role in the organization.
+    pub role: OrgRole,
+}
+
+/// Get User Organizations
+///
+/// Get the organizations a user is in.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}/orgs",
+    tag = "Users",
+    params(
+        ("id" = String, description = "The user ID."),
+    ),
+    responses(
+        (status = 200, description = "Found organizations!", body = Vec<UserOrganization>),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured! The user may not exist!"),
+    ),
+)]
+#[debug_handler]
+pub async fn list_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<UserOrganization>>> {
+    let mut conn = state.pool.get().await?;
+    let user = get_user(id, &mut conn).await?;
+
+    Ok(Json(
+        get_user_orgs(user.id, &mut conn)
+            .await?
+            .into_iter()
+            .map(|(member, org)| UserOrganization {
+                org,
+                role: member.role,
+            })
+            .collect(),
+    ))
+}
@@ -7,8 +7,8 @@ use axum::{
 use axum_extra::extract::CookieJar;
 use chrono::Utc;
 use db::{
-    get_user, package_authors, packages, users, Package, PackageAuthor, PackageData,
-    PackageVisibility, User,
+    get_user, get_user_orgs, package_authors, packages, users, DbConn, Package, PackageAuthor,
+    PackageData, PackageVisibility, TokenScope, User,
 };
 use diesel::{BelongingToDsl, ExpressionMethods, GroupedBy, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
@@ -56,25 +56,9 @@ pub async fn list_handler(
         let now = Utc::now().timestamp_millis();
 
         if *expires > now {
-            return match get_user_from_req(&jar, &headers, &mut conn).await {
-                Ok(user) => Ok(Json(
-                    data.clone()
-                        .into_iter()
-                        .filter(|v| {
-                            v.visibility == PackageVisibility::Public
-                                || v.authors.iter().any(|v| v.github_id == user.github_id)
-                                || user.admin
-                        })
-                        .collect(),
-                )),
-
-                Err(_) => Ok(Json(
-                    data.clone()
-                        .into_iter()
-                        .filter(|v| v.visibility == PackageVisibility::Public)
-                        .collect(),
-                )),
-            };
+            return Ok(Json(
+                visible_packages(data.clone(), &jar, &headers, &mut conn).await?,
+            ));
         }
     }
 
@@ -110,23 +94,40 @@ pub async fn list_handler(
         (Utc::now().timestamp_millis() + CACHE_EXPIRY_MS, res.clone()),
     );
 
-    match get_user_from_req(&jar, &headers, &mut conn).await {
-        Ok(user) => Ok(Json(
-            res.iter()
-                .filter(|v| {
-                    v.visibility == PackageVisibility::Public
-                        || v.authors.iter().any(|v| v.github_id == user.github_id)
-                        || user.admin
-                })
-                .cloned()
-                .collect(),
-        )),
+    Ok(Json(
+        visible_packages(res, &jar, &headers, &mut conn).await?,
+    ))
+}
 
-        Err(_) => Ok(Json(
-            res.iter()
-                .filter(|v| v.visibility == PackageVisibility::Public)
-                .cloned()
-                .collect(),
-        )),
-    }
+/// Filter a list of packages down to the ones the user making a request can see.
+/// Private packages are only visible to their authors, members of the organization
+/// that owns them, and admins.
+pub async fn visible_packages(
+    pkgs: Vec<PackageData>,
+    jar: &CookieJar,
+    headers: &HeaderMap,
+    conn: &mut DbConn,
+) -> Result<Vec<PackageData>> {
+    let Ok(user) = get_user_from_req(jar, headers, conn, TokenScope::Read, None).await else {
+        return Ok(pkgs
+            .into_iter()
+            .filter(|v| v.visibility == PackageVisibility::Public)
+            .collect());
+    };
+
+    let orgs = get_user_orgs(user.id, conn)
+        .await?
+        .into_iter()
+        .map(|(member, _)| member.organization)
+        .collect::<Vec<_>>();
+
+    Ok(pkgs
+        .into_iter()
+        .filter(|v| {
+            v.visibility == PackageVisibility::Public
+                || v.authors.iter().any(|v| v.id == user.id)
+                || v.organization.is_some_and(|it| orgs.contains(&it))
+                || user.admin
+        })
+        .collect())
 }
@@ -0,0 +1,105 @@
+use crate::{auth::get_session_user_from_req, state::AppState, Result};
+use app_core::AppError;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use db::{get_user_sessions, user_tokens, UserToken};
+use diesel::{delete, ExpressionMethods};
+use diesel_async::RunQueryDsl;
+
+/// One of a user's active login sessions.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct Session {
+    /// The session's token.
+    #[serde(flatten)]
+    pub token: UserToken,
+
+    /// Whether this is the session the request was made with.
+    pub current: bool,
+}
+
+/// List Sessions
+///
+/// List the current user's active login sessions.
+/// This needs a login token.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/sessions",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Found sessions!", body = Vec<Session>),
+        (status = 401, description = "Personal API tokens can't manage sessions!"),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn list_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+) -> Result<Json<Vec<Session>>> {
+    let mut conn = state.pool.get().await?;
+    let (user, current) = get_session_user_from_req(&jar, &headers, &mut conn).await?;
+
+    Ok(Json(
+        get_user_sessions(user.id, &mut conn)
+            .await?
+            .into_iter()
+            .map(|token| Session {
+                current: token.id == current.id,
+                token,
+            })
+            .collect(),
+    ))
+}
+
+/// Revoke Session
+///
+/// Log out one of the current user's sessions.
+/// This needs a login token.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/sessions/{id}",
+    tag = "Users",
+    params(
+        ("id" = i32, Path, description = "The session's ID"),
+    ),
+    responses(
+        (status = 200, description = "Revoked session!", body = String),
+        (status = 404, description = "Session not found!"),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn revoke_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+) -> Result<String> {
+    let mut conn = state.pool.get().await?;
+    let (user, _) = get_session_user_from_req(&jar, &headers, &mut conn).await?;
+
+    let deleted = delete(user_tokens::table)
+        .filter(user_tokens::id.eq(id))
+        .filter(user_tokens::user_id.eq(user.id))
+        .execute(&mut conn)
+        .await?;
+
+    if deleted == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok("Revoked session successfully!".into())
+}
@@ -0,0 +1,217 @@
+use crate::{auth::get_session_user_from_req, state::AppState, Result};
+use app_core::AppError;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use chrono::{NaiveDateTime, Utc};
+use db::{
+    api_tokens, generate_api_token_value, get_package, get_user_api_tokens, has_package_permission,
+    hash_token, token_prefix, ApiToken, NewApiToken, PackagePermission, TokenScope,
+};
+use diesel::{delete, insert_into, ExpressionMethods, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+/// A request to create a personal API token.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct CreateTokenRequest {
+    /// The token's name.
+    pub name: String,
+
+    /// What the token is allowed to do.
+    pub scopes: Vec<TokenScope>,
+
+    /// The ID or slug of a package to restrict the token to.
+    #[serde(default)]
+    pub package: Option<String>,
+
+    /// When the token should expire. If this is missing, it never expires.
+    #[serde(default)]
+    pub expires: Option<NaiveDateTime>,
+}
+
+/// A newly created personal API token, including its value.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct CreatedToken {
+    /// The token.
+    #[serde(flatten)]
+    pub token: ApiToken,
+
+    /// The token's value.
+    /// This won't be shown again, so it needs to be saved somewhere!
+    pub value: String,
+}
+
+/// List Tokens
+///
+/// List the current user's personal API tokens.
+/// This needs a login token.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/tokens",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Found tokens!", body = Vec<ApiToken>),
+        (status = 401, description = "Personal API tokens can't manage tokens!"),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn list_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ApiToken>>> {
+    let mut conn = state.pool.get().await?;
+    let (user, _) = get_session_user_from_req(&jar, &headers, &mut conn).await?;
+
+    Ok(Json(get_user_api_tokens(user.id, &mut conn).await?))
+}
+
+/// Create Token
+///
+/// Create a personal API token for the current user.
+/// This needs a login token.
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me/tokens",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Created token!", body = CreatedToken),
+        (status = 400, description = "The token's name, scopes, or expiration date was invalid!"),
+        (status = 401, description = "You can't create a token with those permissions!"),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    request_body(content = CreateTokenRequest, description = "The token to create"),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn create_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+    Json(body): Json<CreateTokenRequest>,
+) -> Result<Json<CreatedToken>> {
+    let mut conn = state.pool.get().await?;
+    let (user, _) = get_session_user_from_req(&jar, &headers, &mut conn).await?;
+    let name = body.name.trim();
+
+    if name.is_empty() {
+        return Err(AppError::BadRequest("Tokens need a name!".into()));
+    }
+
+    if body.scopes.is_empty() {
+        return Err(AppError::BadRequest(
+            "Tokens need at least one scope!".into(),
+        ));
+    }
+
+    if body.scopes.contains(&TokenScope::Admin) && !user.admin {
+        return Err(AppError::Unauthorized);
+    }
+
+    if body.expires.is_some_and(|it| it <= Utc::now().naive_utc()) {
+        return Err(AppError::BadRequest(
+            "Tokens can't expire in the past!".into(),
+        ));
+    }
+
+    let package = match body.package {
+        Some(id) => {
+            let pkg = get_package(id, &mut conn).await?;
+
+            if !has_package_permission(
+                &user,
+                pkg.id,
+                pkg.organization,
+                PackagePermission::View,
+                &mut conn,
+            )
+            .await?
+            {
+                return Err(AppError::Unauthorized);
+            }
+
+            Some(pkg.id)
+        }
+
+        None => None,
+    };
+
+    let mut scopes = body.scopes;
+
+    scopes.sort();
+    scopes.dedup();
+
+    let value = generate_api_token_value();
+
+    let token = insert_into(api_tokens::table)
+        .values(&NewApiToken {
+            user_id: user.id,
+            name: name.into(),
+            hash: hash_token(&value),
+            prefix: token_prefix(&value),
+            scopes,
+            package,
+            expires: body.expires,
+        })
+        .returning(ApiToken::as_returning())
+        .get_result(&mut conn)
+        .await?;
+
+    Ok(Json(CreatedToken { token, value }))
+}
+
+/// Delete Token
+///
+/// Delete one of the current user's personal API tokens.
+/// This needs a login token.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/tokens/{id}",
+    tag = "Users",
+    params(
+        ("id" = i32, Path, description = "The token's ID"),
+    ),
+    responses(
+        (status = 200, description = "Deleted token!", body = String),
+        (status = 404, description = "Token not found!"),
+        (status = INTERNAL_SERVER_ERROR, description = "An internal error occured!"),
+    ),
+    security(
+        ("api_auth_token" = []),
+    ),
+)]
+#[debug_handler]
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+) -> Result<String> {
+    let mut conn = state.pool.get().await?;
+    let (user, _) = get_session_user_from_req(&jar, &headers, &mut conn).await?;
+
+    let deleted = delete(api_tokens::table)
+        .filter(api_tokens::id.eq(id))
+        .filter(api_tokens::user_id.eq(user.id))
+        .execute(&mut conn)
+        .await?;
+
+    if deleted == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok("Deleted token successfully!".into())
+}
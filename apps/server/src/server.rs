@@ -1,17 +1,18 @@
 use crate::{
     glue::make_glue,
+    jobs::Scheduler,
     routes::{
         create_router,
         meta::{loaders::ModLoader, vers::GameVersion},
     },
     state::AppState,
-    worker::run_worker,
+    verify::PackageVerifier,
     Tag,
 };
 use anyhow::Result;
 use app_config::{get_config, AppConfig};
-use axum::{body::Bytes, extract::connect_info::IntoMakeServiceWithConnectInfo, serve, Router};
-use db::{create_connection, run_migrations, DbPool};
+use axum::{extract::connect_info::IntoMakeServiceWithConnectInfo, serve, Router};
+use db::{create_connection, run_migrations};
 use jsglue::{glue::Glue, util::is_debug};
 use search::MeiliPackage;
 use std::net::{IpAddr, SocketAddr};
@@ -19,7 +20,6 @@ use tokio::{join, net::TcpListener};
 
 pub struct ModHost {
     config: AppConfig,
-    pool: DbPool,
     glue: Glue,
     state: AppState,
     addr: SocketAddr,
@@ -28,7 +28,7 @@ pub struct ModHost {
 
 impl ModHost {
     /// Create a new server instance.
-    pub async fn new(verifier: Box<dyn Fn(Bytes) -> bool + Send + Sync>) -> Result<Self> {
+    pub async fn new(verifier: Box<dyn PackageVerifier>) -> Result<Self> {
         info!("Starting app...");
         info!("Getting config...");
 
@@ -64,7 +64,6 @@ impl ModHost {
 
         Ok(Self {
             config,
-            pool,
             state,
             glue,
             addr,
@@ -107,9 +106,9 @@ impl ModHost {
 
     /// Run the server!
     pub async fn run(self) -> Result<()> {
-        info!("Starting worker...");
+        info!("Starting jobs...");
 
-        run_worker(self.pool);
+        Scheduler::with_default_jobs(self.state.clone()).start();
 
         info!("Binding listener...");
 
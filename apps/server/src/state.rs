@@ -1,52 +1,57 @@
 use crate::{
+    auth::{oauth_state::OAuthStateKeys, providers::AuthProviders},
     routes::meta::{loaders::ModLoader, tags::Tag, vers::GameVersion},
+    util::tracker::DownloadTracker,
+    verify::PackageVerifier,
     Result,
 };
 use app_config::AppConfig;
-use axum::body::Bytes;
 use db::DbPool;
-use oauth2::basic::BasicClient;
-use s3::Bucket;
 use search::MeilisearchService;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+use storage::{gallery_storage, packages_storage, StorageBackend};
 
 #[derive(Clone)]
 pub struct BucketState {
-    pub packages: Box<Bucket>,
-    pub gallery: Box<Bucket>,
+    pub packages: Arc<dyn StorageBackend>,
+    pub gallery: Arc<dyn StorageBackend>,
 }
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: DbPool,
-    pub auth: BasicClient,
+    pub auth: AuthProviders,
+    pub oauth_state: OAuthStateKeys,
     pub buckets: BucketState,
     pub config: AppConfig,
     pub loaders: Vec<ModLoader>,
     pub game_versions: Vec<GameVersion>,
     pub tags: Vec<Tag>,
     pub search: MeilisearchService,
-    pub verifier: Arc<Box<dyn Fn(Bytes) -> bool + Send + Sync>>,
+    pub verifier: Arc<Box<dyn PackageVerifier>>,
+    pub downloads: DownloadTracker,
 }
 
 impl AppState {
     pub fn new(
         pool: DbPool,
         config: &AppConfig,
-        verifier: Box<dyn Fn(Bytes) -> bool + Send + Sync>,
+        verifier: Box<dyn PackageVerifier>,
     ) -> Result<Self> {
         Ok(Self {
             pool,
-            auth: config.auth.github()?,
+            auth: AuthProviders::from_config(&config.auth)?,
+            oauth_state: OAuthStateKeys::from_config(&config.auth),
             buckets: BucketState {
-                packages: config.storage.packages()?,
-                gallery: config.storage.gallery()?,
+                packages: packages_storage(&config.storage)?,
+                gallery: gallery_storage(&config.storage)?,
             },
             config: config.clone(),
             loaders: vec![],
             game_versions: vec![],
             tags: vec![],
             verifier: Arc::new(verifier),
+            downloads: DownloadTracker::new(Duration::from_secs(config.server.download_window)),
             search: MeilisearchService::new(config)?,
         })
     }
@@ -0,0 +1,237 @@
+use crate::Result;
+use axum::{
+    body::Body,
+    http::{
+        header::{
+            ACCEPT_RANGES, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG,
+            IF_NONE_MATCH, IF_RANGE, RANGE,
+        },
+        HeaderMap, StatusCode,
+    },
+    response::Response,
+};
+use storage::{content_disposition, StorageBackend};
+
+/// The part of a file requested by a `Range` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ByteRange {
+    /// The whole file.
+    Full,
+
+    /// The bytes from `start` to `end` (inclusive).
+    Partial(u64, u64),
+
+    /// A range that lies outside of the file.
+    Unsatisfiable,
+}
+
+impl ByteRange {
+    /// Parse a `Range` header value for a file of the given size.
+    /// Anything other than a single byte range is ignored, and the whole file is sent.
+    pub fn parse(value: &str, size: u64) -> Self {
+        let Some(spec) = value.trim().strip_prefix("bytes=") else {
+            return Self::Full;
+        };
+
+        if spec.contains(',') {
+            return Self::Full;
+        }
+
+        let Some((start, end)) = spec.trim().split_once('-') else {
+            return Self::Full;
+        };
+
+        let (start, end) = match (start.trim(), end.trim()) {
+            ("", "") => return Self::Full,
+
+            ("", suffix) => match suffix.parse::<u64>() {
+                Ok(0) => return Self::Unsatisfiable,
+                Ok(suffix) => (size.saturating_sub(suffix), size.saturating_sub(1)),
+                Err(_) => return Self::Full,
+            },
+
+            (start, end) => {
+                let Ok(start) = start.parse::<u64>() else {
+                    return Self::Full;
+                };
+
+                let end = match end {
+                    "" => size.saturating_sub(1),
+
+                    end => match end.parse::<u64>() {
+                        Ok(end) if end >= start => end.min(size.saturating_sub(1)),
+                        _ => return Self::Full,
+                    },
+                };
+
+                (start, end)
+            }
+        };
+
+        if size == 0 || start >= size {
+            Self::Unsatisfiable
+        } else {
+            Self::Partial(start, end)
+        }
+    }
+}
+
+/// Stream a stored file to the client as an attachment, with support for
+/// `ETag`s (`If-None-Match`/`If-Range`) and single byte ranges.
+/// Files are content-addressed, so the key makes for a strong `ETag`.
+pub async fn stream_file(
+    storage: &dyn StorageBackend,
+    key: &str,
+    size: u64,
+    file_name: &str,
+    headers: &HeaderMap,
+) -> Result<Response> {
+    let etag = format!("\"{}\"", key);
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+
+    if header(IF_NONE_MATCH).is_some_and(|v| v.split(',').any(|v| v.trim() == etag)) {
+        return Ok(Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(ETAG, &etag)
+            .body(Body::empty())?);
+    }
+
+    let range = match (header(RANGE), header(IF_RANGE)) {
+        (Some(_), Some(if_range)) if if_range.trim() != etag => ByteRange::Full,
+        (Some(range), _) => ByteRange::parse(range, size),
+        (None, _) => ByteRange::Full,
+    };
+
+    let res = Response::builder()
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .header(CONTENT_DISPOSITION, content_disposition(file_name))
+        .header(ACCEPT_RANGES, "bytes")
+        .header(ETAG, &etag);
+
+    Ok(match range {
+        ByteRange::Full => res
+            .header(CONTENT_LENGTH, size)
+            .body(Body::from_stream(storage.stream(key, None).await?))?,
+
+        ByteRange::Partial(start, end) => res
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(CONTENT_LENGTH, end - start + 1)
+            .header(CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, size))
+            .body(Body::from_stream(
+                storage.stream(key, Some((start, end))).await?,
+            ))?,
+
+        ByteRange::Unsatisfiable => res
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(CONTENT_RANGE, format!("bytes */{}", size))
+            .body(Body::empty())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storage::FsStorage;
+
+    #[test]
+    fn suffix_ranges_count_from_the_end() {
+        assert_eq!(ByteRange::parse("bytes=-4", 10), ByteRange::Partial(6, 9));
+        assert_eq!(ByteRange::parse("bytes=-20", 10), ByteRange::Partial(0, 9));
+        assert_eq!(ByteRange::parse("bytes=-0", 10), ByteRange::Unsatisfiable);
+    }
+
+    #[test]
+    fn open_ended_ranges_run_to_the_end() {
+        assert_eq!(ByteRange::parse("bytes=4-", 10), ByteRange::Partial(4, 9));
+        assert_eq!(ByteRange::parse("bytes=2-5", 10), ByteRange::Partial(2, 5));
+    }
+
+    #[test]
+    fn ends_past_the_end_are_clamped() {
+        assert_eq!(
+            ByteRange::parse("bytes=4-100", 10),
+            ByteRange::Partial(4, 9)
+        );
+    }
+
+    #[test]
+    fn starts_past_the_end_are_unsatisfiable() {
+        assert_eq!(ByteRange::parse("bytes=10-", 10), ByteRange::Unsatisfiable);
+        assert_eq!(
+            ByteRange::parse("bytes=12-20", 10),
+            ByteRange::Unsatisfiable
+        );
+    }
+
+    #[test]
+    fn empty_files_are_unsatisfiable() {
+        assert_eq!(ByteRange::parse("bytes=0-", 0), ByteRange::Unsatisfiable);
+        assert_eq!(ByteRange::parse("bytes=-5", 0), ByteRange::Unsatisfiable);
+    }
+
+    #[test]
+    fn invalid_and_multiple_ranges_send_the_whole_file() {
+        assert_eq!(ByteRange::parse("bytes=5-2", 10), ByteRange::Full);
+        assert_eq!(ByteRange::parse("bytes=0-1,4-5", 10), ByteRange::Full);
+        assert_eq!(ByteRange::parse("bytes=-", 10), ByteRange::Full);
+        assert_eq!(ByteRange::parse("bytes=a-b", 10), ByteRange::Full);
+        assert_eq!(ByteRange::parse("items=0-1", 10), ByteRange::Full);
+    }
+
+    async fn download(headers: &[(&'static str, &str)]) -> (StatusCode, Vec<u8>) {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FsStorage::new(dir.path());
+        let mut map = HeaderMap::new();
+
+        storage.put("file", b"0123456789").await.unwrap();
+
+        for (name, value) in headers {
+            map.insert(*name, value.parse().unwrap());
+        }
+
+        let res = stream_file(&storage, "file", 10, "file.zip", &map)
+            .await
+            .unwrap();
+
+        let status = res.status();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn ranges_stream_part_of_the_file() {
+        let (status, body) = download(&[("Range", "bytes=2-5")]).await;
+
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body, b"2345");
+
+        let (status, body) = download(&[("Range", "bytes=20-")]).await;
+
+        assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn if_range_mismatches_send_the_whole_file() {
+        let (status, body) = download(&[("Range", "bytes=2-5"), ("If-Range", "\"other\"")]).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"0123456789");
+
+        let (status, body) = download(&[("Range", "bytes=2-5"), ("If-Range", "\"file\"")]).await;
+
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body, b"2345");
+    }
+
+    #[tokio::test]
+    async fn matching_etags_are_not_modified() {
+        let (status, body) = download(&[("If-None-Match", "\"a\", \"file\"")]).await;
+
+        assert_eq!(status, StatusCode::NOT_MODIFIED);
+        assert!(body.is_empty());
+    }
+}
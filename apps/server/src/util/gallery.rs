@@ -2,12 +2,7 @@ use crate::{state::AppState, Result};
 use db::{GalleryImage, PublicGalleryImage};
 
 pub async fn get_image(id: impl AsRef<str>, state: &AppState) -> Result<Vec<u8>> {
-    Ok(state
-        .buckets
-        .gallery
-        .get_object(format!("/{}", id.as_ref()))
-        .await?
-        .to_vec())
+    state.buckets.gallery.get(id.as_ref()).await
 }
 
 pub async fn transform_gallery_image(img: GalleryImage) -> Result<PublicGalleryImage> {
@@ -0,0 +1,68 @@
+use sha1::{Digest, Sha1};
+use sha2::{Sha256, Sha512};
+
+/// The hashes and size of a stored file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileHashes {
+    /// The SHA-1 hash of the file.
+    pub sha1: String,
+
+    /// The SHA-256 hash of the file.
+    /// Files are stored under this, so it doubles as the file's ID.
+    pub sha256: String,
+
+    /// The SHA-512 hash of the file.
+    pub sha512: String,
+
+    /// The size of the file, in bytes.
+    pub size: i64,
+}
+
+impl FileHashes {
+    /// Hash a file's contents.
+    pub fn new(data: &[u8]) -> Self {
+        let mut hasher = FileHasher::new();
+
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    /// The key this file is stored under.
+    pub fn file_id(&self) -> String {
+        self.sha256.clone()
+    }
+}
+
+/// Hashes a file as it is received, one chunk at a time.
+#[derive(Debug, Clone, Default)]
+pub struct FileHasher {
+    sha1: Sha1,
+    sha256: Sha256,
+    sha512: Sha512,
+    size: i64,
+}
+
+impl FileHasher {
+    /// Create a new hasher.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a chunk of the file.
+    pub fn update(&mut self, data: &[u8]) {
+        self.sha1.update(data);
+        self.sha256.update(data);
+        self.sha512.update(data);
+        self.size += data.len() as i64;
+    }
+
+    /// Get the hashes of everything added so far.
+    pub fn finish(self) -> FileHashes {
+        FileHashes {
+            sha1: format!("{:x}", self.sha1.finalize()),
+            sha256: format!("{:x}", self.sha256.finalize()),
+            sha512: format!("{:x}", self.sha512.finalize()),
+            size: self.size,
+        }
+    }
+}
@@ -1,6 +1,13 @@
+pub mod download;
 pub mod gallery;
+pub mod hash;
+pub mod relations;
+pub mod resolve;
 pub mod sanitize;
 pub mod scheme;
+pub mod stats;
+pub mod tracker;
+pub mod upload;
 pub mod versions;
 
 use octocrab::Octocrab;
@@ -0,0 +1,233 @@
+use crate::util::versions::{compare_versions, matches_range, parse_range};
+use app_core::{AppError, Result};
+use db::{
+    get_dependencies, get_package, get_requirements, has_package_permission, package_versions,
+    DbConn, Package, PackageManifest, PackagePermission, PackageVersion, PackageVisibility,
+    RelationKind, User, VersionRelation,
+};
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+/// Split a manifest relation entry into its package ID/slug and optional version.
+/// Entries are formatted as either `package` or `package@version`, where the
+/// version can be an exact version number/name or a semver requirement.
+pub fn parse_relation(spec: &str) -> (&str, Option<&str>) {
+    match spec.split_once('@') {
+        Some((pkg, ver)) if !ver.trim().is_empty() => (pkg.trim(), Some(ver.trim())),
+        Some((pkg, _)) => (pkg.trim(), None),
+        None => (spec.trim(), None),
+    }
+}
+
+/// Check if a user can see a package.
+/// Private packages can only be seen by users with access to them.
+pub async fn can_see(pkg: &Package, user: Option<&User>, conn: &mut DbConn) -> Result<bool> {
+    if pkg.visibility != PackageVisibility::Private {
+        return Ok(true);
+    }
+
+    let Some(user) = user else {
+        return Ok(false);
+    };
+
+    has_package_permission(
+        user,
+        pkg.id,
+        pkg.organization,
+        PackagePermission::View,
+        conn,
+    )
+    .await
+}
+
+/// Filter a package's versions down to the ones matching a requirement.
+/// A requirement that's exactly a version's number or name only matches that
+/// version, and anything else is treated as a semver range.
+/// No requirement matches every version.
+pub fn matching_versions(
+    versions: Vec<PackageVersion>,
+    req: Option<&str>,
+) -> std::result::Result<Vec<PackageVersion>, String> {
+    let Some(req) = req else {
+        return Ok(versions);
+    };
+
+    let is_exact = |v: &PackageVersion| v.version_number == req || v.name == req;
+
+    if versions.iter().any(is_exact) {
+        return Ok(versions.into_iter().filter(is_exact).collect());
+    }
+
+    let req = parse_range(req)?;
+
+    Ok(versions
+        .into_iter()
+        .filter(|v| matches_range(&req, &v.version_number))
+        .collect())
+}
+
+/// Pick the version a dependency should use out of the ones matching it.
+/// Versions that work on one of the given loaders and game versions come first,
+/// then ones that haven't been yanked, then the newest.
+pub fn pick_dependency(
+    versions: Vec<PackageVersion>,
+    loaders: &[Option<String>],
+    game_versions: &[Option<String>],
+) -> Option<PackageVersion> {
+    let compatible = |v: &PackageVersion| {
+        (loaders.is_empty() || v.loaders.iter().any(|it| loaders.contains(it)))
+            && (game_versions.is_empty()
+                || v.game_versions.iter().any(|it| game_versions.contains(it)))
+    };
+
+    versions.into_iter().max_by(|a, b| {
+        compatible(a)
+            .cmp(&compatible(b))
+            .then_with(|| (!a.yanked).cmp(&!b.yanked))
+            .then_with(|| compare_versions(&a.version_number, &b.version_number))
+    })
+}
+
+/// Find a package that a user can see, along with all of its versions.
+/// Returns `None` if the package doesn't exist or the user can't see it.
+pub async fn find_visible_package(
+    id: impl AsRef<str>,
+    user: Option<&User>,
+    conn: &mut DbConn,
+) -> Result<Option<(Package, Vec<PackageVersion>)>> {
+    let pkg = match get_package(id.as_ref(), conn).await {
+        Ok(pkg) => pkg,
+        Err(AppError::Database(diesel::result::Error::NotFound)) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    if !can_see(&pkg, user, conn).await? {
+        return Ok(None);
+    }
+
+    let versions = get_versions(pkg.id, conn).await?;
+
+    Ok(Some((pkg, versions)))
+}
+
+/// Get all of a package's versions.
+async fn get_versions(pkg: i32, conn: &mut DbConn) -> Result<Vec<PackageVersion>> {
+    Ok(package_versions::table
+        .filter(package_versions::package.eq(pkg))
+        .select(PackageVersion::as_select())
+        .load(conn)
+        .await?)
+}
+
+/// A manifest's dependencies and incompatibilities, ready to be stored with a new version.
+#[derive(Debug, Clone, Default)]
+pub struct ManifestRelations {
+    /// The versions each relation matches right now, by version ID.
+    pub relations: Vec<(i32, RelationKind)>,
+
+    /// The requirements the relations were declared with, by package ID,
+    /// so they can be resolved again when newer versions come out.
+    pub requirements: Vec<(i32, RelationKind, Option<String>)>,
+}
+
+/// Resolve a manifest's dependencies and incompatibilities for a new version
+/// that works on the given loaders and game versions.
+/// Dependencies resolve to the best matching version (see [`pick_dependency`])
+/// and must exist. Incompatibilities resolve to every matching version, and
+/// unknown packages are ignored. Packages the uploader can't see count as unknown.
+pub async fn resolve_manifest_relations(
+    manifest: &PackageManifest,
+    loaders: &[Option<String>],
+    game_versions: &[Option<String>],
+    user: &User,
+    conn: &mut DbConn,
+) -> Result<ManifestRelations> {
+    let mut resolved = ManifestRelations::default();
+
+    for spec in &manifest.dependencies {
+        let (id, req) = parse_relation(spec);
+        let unresolved =
+            || AppError::BadRequest(format!("Could not resolve dependency: '{}'", spec));
+
+        let Some((pkg, versions)) = find_visible_package(id, Some(user), conn).await? else {
+            return Err(unresolved());
+        };
+
+        let versions = matching_versions(versions, req).map_err(AppError::BadRequest)?;
+        let ver = pick_dependency(versions, loaders, game_versions).ok_or_else(unresolved)?;
+
+        resolved.relations.push((ver.id, RelationKind::Dependency));
+
+        resolved
+            .requirements
+            .push((pkg.id, RelationKind::Dependency, req.map(String::from)));
+    }
+
+    for spec in &manifest.incompatibilities {
+        let (id, req) = parse_relation(spec);
+
+        let Some((pkg, versions)) = find_visible_package(id, Some(user), conn).await? else {
+            continue;
+        };
+
+        for ver in matching_versions(versions, req).map_err(AppError::BadRequest)? {
+            resolved
+                .relations
+                .push((ver.id, RelationKind::Incompatibility));
+        }
+
+        resolved
+            .requirements
+            .push((pkg.id, RelationKind::Incompatibility, req.map(String::from)));
+    }
+
+    Ok(resolved)
+}
+
+/// Get the versions that a package version depends on (or is incompatible with),
+/// resolving its requirements against the versions that exist now.
+/// Versions uploaded before requirements were stored fall back to the versions
+/// that were picked when they were uploaded.
+pub async fn get_version_dependencies(
+    ver: &PackageVersion,
+    conn: &mut DbConn,
+) -> Result<Vec<VersionRelation>> {
+    let reqs = get_requirements(&[ver.id], conn).await?;
+
+    if reqs.is_empty() {
+        return get_dependencies(ver.id, conn).await;
+    }
+
+    let mut relations = Vec::new();
+
+    for req in reqs {
+        let pkg = get_package(req.package.to_string(), conn).await?;
+        let versions = get_versions(pkg.id, conn).await?;
+
+        let Ok(versions) = matching_versions(versions, req.requirement.as_deref()) else {
+            continue;
+        };
+
+        match req.kind {
+            RelationKind::Dependency => {
+                if let Some(version) = pick_dependency(versions, &ver.loaders, &ver.game_versions) {
+                    relations.push(VersionRelation {
+                        kind: req.kind,
+                        package: pkg,
+                        version,
+                    });
+                }
+            }
+
+            RelationKind::Incompatibility => {
+                relations.extend(versions.into_iter().map(|version| VersionRelation {
+                    kind: req.kind,
+                    package: pkg.clone(),
+                    version,
+                }))
+            }
+        }
+    }
+
+    Ok(relations)
+}
@@ -0,0 +1,309 @@
+use crate::util::{
+    relations::{find_visible_package, matching_versions, parse_relation},
+    versions::compare_versions,
+};
+use app_core::Result;
+use db::{
+    get_requirements, package_relations, package_versions, DbConn, Package, PackageVersion,
+    RelationKind, User,
+};
+use diesel::{ExpressionMethods, JoinOnDsl, QueryDsl};
+use diesel_async::RunQueryDsl;
+use std::collections::{BTreeMap, VecDeque};
+
+/// A package version picked by the resolver.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct ResolvedPackage {
+    /// The package.
+    pub package: Package,
+
+    /// The version of the package that was picked.
+    pub version: PackageVersion,
+}
+
+/// A conflict between two resolved package versions.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct ResolveConflict {
+    /// The version that declared the incompatibility.
+    pub version: PackageVersion,
+
+    /// The version it is incompatible with.
+    pub incompatible_with: PackageVersion,
+}
+
+/// The result of resolving a set of packages.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct ResolveResults {
+    /// The packages (including dependencies) to install.
+    pub packages: Vec<ResolvedPackage>,
+
+    /// Packages that either don't exist, have no version compatible with the
+    /// requested loader and game version, or have no version matching what
+    /// was required of them. Requirements are included as `package@requirement`.
+    pub unresolved: Vec<String>,
+
+    /// Incompatibilities between the resolved versions.
+    pub conflicts: Vec<ResolveConflict>,
+}
+
+/// A requirement declared by a package version, as `(version, package, requirement)`.
+type Requirement = (i32, i32, Option<String>);
+
+/// Resolve a set of packages and their dependencies for a loader and game version.
+/// Packages can be given as `package` or `package@requirement`, like in a manifest.
+/// Each package resolves to its newest compatible version that hasn't been yanked
+/// and matches what was required of it.
+pub async fn resolve_packages(
+    packages: Vec<String>,
+    loader: impl AsRef<str>,
+    game_version: impl AsRef<str>,
+    user: Option<&User>,
+    conn: &mut DbConn,
+) -> Result<ResolveResults> {
+    let loader = Some(loader.as_ref().to_string());
+    let game_version = Some(game_version.as_ref().to_string());
+
+    let mut queue = packages
+        .iter()
+        .map(|spec| {
+            let (id, req) = parse_relation(spec);
+
+            (id.to_string(), req.map(String::from))
+        })
+        .collect::<VecDeque<_>>();
+
+    let mut resolved = BTreeMap::<i32, ResolvedPackage>::new();
+    let mut unresolved = Vec::new();
+
+    while let Some((id, req)) = queue.pop_front() {
+        let name = match &req {
+            Some(req) => format!("{}@{}", id, req),
+            None => id.clone(),
+        };
+
+        let Some((pkg, versions)) = find_visible_package(&id, user, conn).await? else {
+            unresolved.push(name);
+            continue;
+        };
+
+        if let Some(prev) = resolved.get(&pkg.id) {
+            if !satisfies(&prev.version, req.as_deref()) {
+                unresolved.push(name);
+            }
+
+            continue;
+        }
+
+        let Some(version) = pick_version(versions, req.as_deref(), &loader, &game_version) else {
+            unresolved.push(name);
+            continue;
+        };
+
+        let deps = declared_requirements(&[version.id], RelationKind::Dependency, conn).await?;
+
+        queue.extend(deps.into_iter().map(|(_, pkg, req)| (pkg.to_string(), req)));
+
+        resolved.insert(
+            pkg.id,
+            ResolvedPackage {
+                package: pkg,
+                version,
+            },
+        );
+    }
+
+    let versions = resolved
+        .values()
+        .map(|v| v.version.clone())
+        .collect::<Vec<_>>();
+
+    let ids = versions.iter().map(|v| v.id).collect::<Vec<_>>();
+    let incompatibilities =
+        declared_requirements(&ids, RelationKind::Incompatibility, conn).await?;
+
+    Ok(ResolveResults {
+        packages: resolved.into_values().collect(),
+        unresolved,
+        conflicts: find_conflicts(&versions, &incompatibilities),
+    })
+}
+
+/// Check if a version matches a requirement.
+pub fn satisfies(version: &PackageVersion, req: Option<&str>) -> bool {
+    matches!(matching_versions(vec![version.clone()], req), Ok(v) if !v.is_empty())
+}
+
+/// Pick the newest version matching a requirement that works on a loader and
+/// game version and hasn't been yanked.
+pub fn pick_version(
+    versions: Vec<PackageVersion>,
+    req: Option<&str>,
+    loader: &Option<String>,
+    game_version: &Option<String>,
+) -> Option<PackageVersion> {
+    matching_versions(versions, req)
+        .ok()?
+        .into_iter()
+        .filter(|v| {
+            !v.yanked && v.loaders.contains(loader) && v.game_versions.contains(game_version)
+        })
+        .max_by(|a, b| compare_versions(&a.version_number, &b.version_number))
+}
+
+/// Find the conflicts between resolved versions, given the incompatibilities
+/// they declared. A version conflicts with another if the other one matches
+/// the requirement it declared on that package.
+pub fn find_conflicts(
+    versions: &[PackageVersion],
+    incompatibilities: &[Requirement],
+) -> Vec<ResolveConflict> {
+    let mut conflicts = incompatibilities
+        .iter()
+        .filter_map(|(ver, pkg, req)| {
+            let version = versions.iter().find(|v| v.id == *ver)?;
+            let other = versions.iter().find(|v| v.package == *pkg)?;
+
+            satisfies(other, req.as_deref()).then(|| ResolveConflict {
+                version: version.clone(),
+                incompatible_with: other.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    conflicts.sort();
+    conflicts.dedup();
+    conflicts
+}
+
+/// Get the requirements of a kind that a set of versions declared on other packages.
+/// Versions uploaded before requirements were stored require exactly the versions
+/// that were picked when they were uploaded.
+async fn declared_requirements(
+    vers: &[i32],
+    kind: RelationKind,
+    conn: &mut DbConn,
+) -> Result<Vec<Requirement>> {
+    let stored = get_requirements(vers, conn).await?;
+
+    let legacy = vers
+        .iter()
+        .filter(|v| !stored.iter().any(|r| r.version == **v))
+        .copied()
+        .collect::<Vec<_>>();
+
+    let mut reqs = stored
+        .into_iter()
+        .filter(|r| r.kind == kind)
+        .map(|r| (r.version, r.package, r.requirement))
+        .collect::<Vec<_>>();
+
+    let pinned = package_relations::table
+        .inner_join(
+            package_versions::table.on(package_versions::id.eq(package_relations::dependency)),
+        )
+        .filter(package_relations::package.eq_any(legacy))
+        .filter(package_relations::kind.eq(kind))
+        .select((
+            package_relations::package,
+            package_versions::package,
+            package_versions::version_number,
+        ))
+        .load::<(i32, i32, String)>(conn)
+        .await?;
+
+    reqs.extend(
+        pinned
+            .into_iter()
+            .map(|(ver, pkg, num)| (ver, pkg, Some(num))),
+    );
+
+    Ok(reqs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+    use db::VersionChannel;
+
+    fn version(id: i32, package: i32, version_number: &str) -> PackageVersion {
+        PackageVersion {
+            id,
+            package,
+            name: version_number.into(),
+            version_number: version_number.into(),
+            file_id: String::new(),
+            changelog: None,
+            loaders: vec![Some("forge".into())],
+            game_versions: vec![Some("1.20.1".into())],
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+            downloads: 0,
+            sha1: String::new(),
+            sha256: None,
+            sha512: None,
+            file_size: None,
+            channel: VersionChannel::Release,
+            yanked: false,
+            deprecation: None,
+        }
+    }
+
+    fn pick(versions: Vec<PackageVersion>, req: Option<&str>) -> Option<String> {
+        pick_version(versions, req, &Some("forge".into()), &Some("1.20.1".into()))
+            .map(|v| v.version_number)
+    }
+
+    #[test]
+    fn picks_the_newest_version_matching_the_requirement() {
+        let versions = vec![
+            version(1, 1, "1.0.0"),
+            version(2, 1, "1.5.0"),
+            version(3, 1, "3.0.0"),
+        ];
+
+        assert_eq!(pick(versions.clone(), None).as_deref(), Some("3.0.0"));
+        assert_eq!(pick(versions.clone(), Some("<2")).as_deref(), Some("1.5.0"));
+        assert_eq!(pick(versions, Some("1.0.0")).as_deref(), Some("1.0.0"));
+    }
+
+    #[test]
+    fn nothing_is_picked_when_no_version_matches() {
+        let mut yanked = version(2, 1, "1.5.0");
+
+        yanked.yanked = true;
+
+        assert_eq!(pick(vec![version(1, 1, "3.0.0"), yanked], Some("<2")), None);
+        assert_eq!(
+            pick(vec![version(1, 1, "1.0.0")], Some("not a range")),
+            None
+        );
+    }
+
+    #[test]
+    fn conflicts_only_match_the_declared_requirement() {
+        let versions = vec![version(1, 1, "1.0.0"), version(2, 2, "3.0.0")];
+
+        let conflicts = find_conflicts(
+            &versions,
+            &[(1, 2, Some(">=3".into())), (1, 2, Some("<2".into()))],
+        );
+
+        assert_eq!(
+            conflicts,
+            [ResolveConflict {
+                version: versions[0].clone(),
+                incompatible_with: versions[1].clone(),
+            }]
+        );
+
+        assert!(find_conflicts(&versions, &[(1, 2, Some("<2".into()))]).is_empty());
+        assert_eq!(find_conflicts(&versions, &[(2, 1, None)]).len(), 1);
+    }
+}
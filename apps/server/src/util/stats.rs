@@ -0,0 +1,364 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use db::{DailyDownloads, PackageVersion};
+use std::collections::BTreeMap;
+
+/// The size of the periods download statistics are grouped into.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl Granularity {
+    /// Get the first day of the period that a day falls in.
+    pub fn start_of(&self, day: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => day,
+            Self::Week => day - Days::new(day.weekday().num_days_from_monday() as u64),
+            Self::Month => day.with_day(1).unwrap_or(day),
+        }
+    }
+
+    /// Get the first day of the period after the one starting on `start`.
+    pub fn next(&self, start: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Day => start.checked_add_days(Days::new(1)),
+            Self::Week => start.checked_add_days(Days::new(7)),
+            Self::Month => start.checked_add_months(Months::new(1)),
+        }
+    }
+
+    /// Get the number of periods between two days (inclusive),
+    /// without listing them.
+    pub fn count(&self, from: NaiveDate, to: NaiveDate) -> u64 {
+        if from > to {
+            return 0;
+        }
+
+        let months = |day: NaiveDate| day.year() as i64 * 12 + day.month0() as i64;
+
+        let count = match self {
+            Self::Day => (to - from).num_days() + 1,
+            Self::Week => (self.start_of(to) - self.start_of(from)).num_days() / 7 + 1,
+            Self::Month => months(to) - months(from) + 1,
+        };
+
+        count as u64
+    }
+
+    /// Get the start of every period between two days (inclusive).
+    /// The first period starts on `from`, even if that's partway through it.
+    pub fn periods(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let mut periods = Vec::new();
+        let mut cur = Some(from);
+
+        while let Some(day) = cur.filter(|v| *v <= to) {
+            periods.push(day);
+            cur = self.next(self.start_of(day));
+        }
+
+        periods
+    }
+}
+
+/// The number of downloads in a period.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct DownloadPoint {
+    /// The first day of the period.
+    pub date: NaiveDate,
+
+    /// The number of downloads in the period.
+    pub downloads: i64,
+}
+
+/// Downloads over time for a package, version, loader, or game version.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct DownloadSeries {
+    /// What these downloads are for (a version number, loader, or game version).
+    pub name: String,
+
+    /// The total number of downloads in the requested range.
+    pub total: i64,
+
+    /// The number of downloads in each period.
+    pub points: Vec<DownloadPoint>,
+}
+
+/// Download statistics for a package.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct DownloadStats {
+    /// The first day included in these statistics.
+    pub from: NaiveDate,
+
+    /// The last day included in these statistics.
+    pub to: NaiveDate,
+
+    /// The size of each period.
+    pub granularity: Granularity,
+
+    /// Downloads for the whole package.
+    pub total: DownloadSeries,
+
+    /// Downloads for each version.
+    pub versions: Vec<DownloadSeries>,
+
+    /// Downloads for each loader, counting every version that supports it.
+    pub loaders: Vec<DownloadSeries>,
+
+    /// Downloads for each game version, counting every version that supports it.
+    pub game_versions: Vec<DownloadSeries>,
+}
+
+/// Group daily download counts into periods, totalled per version, loader, and game version.
+pub fn aggregate_downloads(
+    name: impl Into<String>,
+    rows: &[DailyDownloads],
+    versions: &[PackageVersion],
+    from: NaiveDate,
+    to: NaiveDate,
+    granularity: Granularity,
+) -> DownloadStats {
+    let periods = granularity.periods(from, to);
+    let mut total = BTreeMap::new();
+    let mut by_version = BTreeMap::<String, BTreeMap<NaiveDate, i64>>::new();
+    let mut by_loader = BTreeMap::<String, BTreeMap<NaiveDate, i64>>::new();
+    let mut by_game_version = BTreeMap::<String, BTreeMap<NaiveDate, i64>>::new();
+
+    for row in rows {
+        let period = granularity.start_of(row.day).max(from);
+        let downloads = row.downloads as i64;
+
+        *total.entry(period).or_default() += downloads;
+
+        let Some(ver) = versions.iter().find(|v| v.id == row.version) else {
+            continue;
+        };
+
+        *by_version
+            .entry(ver.version_number.clone())
+            .or_default()
+            .entry(period)
+            .or_default() += downloads;
+
+        for loader in ver.loaders.iter().flatten() {
+            *by_loader
+                .entry(loader.clone())
+                .or_default()
+                .entry(period)
+                .or_default() += downloads;
+        }
+
+        for game_version in ver.game_versions.iter().flatten() {
+            *by_game_version
+                .entry(game_version.clone())
+                .or_default()
+                .entry(period)
+                .or_default() += downloads;
+        }
+    }
+
+    let series = |name: String, counts: &BTreeMap<NaiveDate, i64>| DownloadSeries {
+        name,
+        total: counts.values().sum(),
+        points: periods
+            .iter()
+            .map(|date| DownloadPoint {
+                date: *date,
+                downloads: counts.get(date).copied().unwrap_or_default(),
+            })
+            .collect(),
+    };
+
+    let all_series = |groups: BTreeMap<String, BTreeMap<NaiveDate, i64>>| {
+        groups
+            .into_iter()
+            .map(|(name, counts)| series(name, &counts))
+            .collect()
+    };
+
+    DownloadStats {
+        from,
+        to,
+        granularity,
+        total: series(name.into(), &total),
+        versions: all_series(by_version),
+        loaders: all_series(by_loader),
+        game_versions: all_series(by_game_version),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+    use db::VersionChannel;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn version(id: i32, version_number: &str, loaders: &[&str]) -> PackageVersion {
+        PackageVersion {
+            id,
+            package: 1,
+            name: version_number.into(),
+            version_number: version_number.into(),
+            file_id: String::new(),
+            changelog: None,
+            loaders: loaders.iter().map(|v| Some(v.to_string())).collect(),
+            game_versions: vec![Some("1.20.1".into())],
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+            downloads: 0,
+            sha1: String::new(),
+            sha256: None,
+            sha512: None,
+            file_size: None,
+            channel: VersionChannel::Release,
+            yanked: false,
+            deprecation: None,
+        }
+    }
+
+    fn downloads(version: i32, day: NaiveDate, downloads: i32) -> DailyDownloads {
+        DailyDownloads {
+            package: 1,
+            version,
+            day,
+            downloads,
+        }
+    }
+
+    #[test]
+    fn periods_start_on_from() {
+        // 2024-01-10 is a Wednesday.
+        assert_eq!(
+            Granularity::Day.periods(day(2024, 1, 10), day(2024, 1, 12)),
+            [day(2024, 1, 10), day(2024, 1, 11), day(2024, 1, 12)]
+        );
+
+        assert_eq!(
+            Granularity::Week.periods(day(2024, 1, 10), day(2024, 1, 22)),
+            [day(2024, 1, 10), day(2024, 1, 15), day(2024, 1, 22)]
+        );
+
+        assert_eq!(
+            Granularity::Month.periods(day(2024, 1, 10), day(2024, 3, 1)),
+            [day(2024, 1, 10), day(2024, 2, 1), day(2024, 3, 1)]
+        );
+
+        assert!(Granularity::Day
+            .periods(day(2024, 1, 2), day(2024, 1, 1))
+            .is_empty());
+    }
+
+    #[test]
+    fn count_matches_periods() {
+        let ranges = [
+            (day(2024, 1, 10), day(2024, 1, 10)),
+            (day(2024, 1, 10), day(2024, 1, 14)),
+            (day(2024, 1, 10), day(2024, 1, 15)),
+            (day(2023, 11, 30), day(2024, 3, 1)),
+            (day(2020, 2, 29), day(2024, 2, 28)),
+            (day(2024, 1, 2), day(2024, 1, 1)),
+        ];
+
+        for granularity in [Granularity::Day, Granularity::Week, Granularity::Month] {
+            for (from, to) in ranges {
+                assert_eq!(
+                    granularity.count(from, to),
+                    granularity.periods(from, to).len() as u64,
+                    "{:?} from {} to {}",
+                    granularity,
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn count_does_not_list_huge_ranges() {
+        assert_eq!(
+            Granularity::Day.count(NaiveDate::MIN, NaiveDate::MAX),
+            (NaiveDate::MAX - NaiveDate::MIN).num_days() as u64 + 1
+        );
+    }
+
+    #[test]
+    fn aggregates_downloads_into_periods() {
+        let versions = [
+            version(1, "1.0.0", &["forge"]),
+            version(2, "1.1.0", &["forge", "fabric"]),
+        ];
+
+        let rows = [
+            downloads(1, day(2024, 1, 10), 1),
+            downloads(1, day(2024, 1, 14), 2),
+            downloads(2, day(2024, 1, 15), 4),
+            downloads(2, day(2024, 1, 16), 8),
+            downloads(3, day(2024, 1, 16), 16),
+        ];
+
+        let stats = aggregate_downloads(
+            "pkg",
+            &rows,
+            &versions,
+            day(2024, 1, 10),
+            day(2024, 1, 28),
+            Granularity::Week,
+        );
+
+        let points = |series: &DownloadSeries| {
+            series
+                .points
+                .iter()
+                .map(|v| (v.date, v.downloads))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(stats.total.name, "pkg");
+        assert_eq!(stats.total.total, 31);
+
+        assert_eq!(
+            points(&stats.total),
+            [
+                (day(2024, 1, 10), 3),
+                (day(2024, 1, 15), 28),
+                (day(2024, 1, 22), 0),
+            ]
+        );
+
+        fn totals(series: &[DownloadSeries]) -> Vec<(&str, i64)> {
+            series.iter().map(|v| (v.name.as_str(), v.total)).collect()
+        }
+
+        assert_eq!(totals(&stats.versions), [("1.0.0", 3), ("1.1.0", 12)]);
+        assert_eq!(totals(&stats.loaders), [("fabric", 12), ("forge", 15)]);
+        assert_eq!(totals(&stats.game_versions), [("1.20.1", 15)]);
+        assert_eq!(points(&stats.versions[0])[0], (day(2024, 1, 10), 3));
+    }
+}
@@ -0,0 +1,195 @@
+use axum::http::HeaderMap;
+use parking_lot::Mutex;
+use std::{
+    collections::{hash_map::RandomState, HashMap, HashSet, VecDeque},
+    hash::BuildHasher,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The most downloads remembered at once. Past this, the oldest ones are
+/// forgotten first, even if they're still inside the window.
+const MAX_RECENT: usize = 100_000;
+
+/// Parts of user agents that belong to crawlers and other bots.
+const BOT_AGENTS: &[&str] = &[
+    "bot",
+    "crawl",
+    "spider",
+    "slurp",
+    "scrape",
+    "headless",
+    "facebookexternalhit",
+    "preview",
+    "monitor",
+];
+
+/// Keeps track of recent downloads so repeated ones aren't counted, and of
+/// packages whose download counts changed since the search index was updated.
+#[derive(Debug, Clone)]
+pub struct DownloadTracker {
+    window: Duration,
+    capacity: usize,
+    hasher: RandomState,
+    recent: Arc<Mutex<RecentDownloads>>,
+    dirty: Arc<Mutex<HashSet<i32>>>,
+}
+
+/// Recently counted downloads, keyed by a hash of the client and version.
+/// `order` holds every download in the order it was counted, so expired
+/// ones can be dropped from the front without scanning the whole map.
+#[derive(Debug, Default)]
+struct RecentDownloads {
+    times: HashMap<u64, Instant>,
+    order: VecDeque<(u64, Instant)>,
+}
+
+impl RecentDownloads {
+    /// Drop the oldest download, if it's still the latest one for its key.
+    fn pop_oldest(&mut self) {
+        if let Some((key, time)) = self.order.pop_front() {
+            if self.times.get(&key) == Some(&time) {
+                self.times.remove(&key);
+            }
+        }
+    }
+}
+
+impl DownloadTracker {
+    /// Create a tracker that ignores repeated downloads within a window.
+    pub fn new(window: Duration) -> Self {
+        Self::with_capacity(window, MAX_RECENT)
+    }
+
+    /// Create a tracker that ignores repeated downloads within a window,
+    /// remembering at most `capacity` downloads at once.
+    pub fn with_capacity(window: Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            capacity: capacity.max(1),
+            hasher: RandomState::new(),
+            recent: Arc::new(Mutex::new(RecentDownloads::default())),
+            dirty: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Check whether a client's download of a version should be counted.
+    /// Downloads are only counted once per client per window.
+    pub fn should_count(&self, client: impl AsRef<str>, ver: i32) -> bool {
+        let now = Instant::now();
+        let key = self.hasher.hash_one((client.as_ref(), ver));
+        let mut recent = self.recent.lock();
+
+        while recent
+            .order
+            .front()
+            .is_some_and(|(_, time)| now.duration_since(*time) >= self.window)
+        {
+            recent.pop_oldest();
+        }
+
+        if recent.times.contains_key(&key) {
+            return false;
+        }
+
+        while recent.order.len() >= self.capacity {
+            recent.pop_oldest();
+        }
+
+        recent.times.insert(key, now);
+        recent.order.push_back((key, now));
+
+        true
+    }
+
+    /// Mark a package as needing its search index entry updated.
+    pub fn mark_dirty(&self, pkg: i32) {
+        self.dirty.lock().insert(pkg);
+    }
+
+    /// Take the packages that need their search index entries updated.
+    pub fn take_dirty(&self) -> Vec<i32> {
+        self.dirty.lock().drain().collect()
+    }
+}
+
+/// Check whether a user agent belongs to a known bot.
+pub fn is_bot(user_agent: &str) -> bool {
+    let user_agent = user_agent.to_lowercase();
+
+    BOT_AGENTS.iter().any(|v| user_agent.contains(v))
+}
+
+/// Get the IP address of the client making a request.
+/// If the server is behind a proxy, this is the last address in `X-Forwarded-For`,
+/// which is the one our proxy added. Earlier ones come from the client and can't
+/// be trusted.
+pub fn client_ip(headers: &HeaderMap, addr: SocketAddr, behind_proxy: bool) -> IpAddr {
+    if behind_proxy {
+        if let Some(ip) = headers
+            .get_all("X-Forwarded-For")
+            .iter()
+            .next_back()
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.rsplit(',').next())
+            .and_then(|v| v.trim().parse().ok())
+        {
+            return ip;
+        }
+    }
+
+    addr.ip()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_ip_uses_the_last_forwarded_address() {
+        let addr = SocketAddr::from(([10, 0, 0, 1], 8080));
+        let mut headers = HeaderMap::new();
+
+        headers.insert("X-Forwarded-For", "1.2.3.4, 5.6.7.8".parse().unwrap());
+
+        assert_eq!(client_ip(&headers, addr, false), addr.ip());
+        assert_eq!(client_ip(&headers, addr, true), IpAddr::from([5, 6, 7, 8]));
+
+        headers.append("X-Forwarded-For", "9.9.9.9".parse().unwrap());
+
+        assert_eq!(client_ip(&headers, addr, true), IpAddr::from([9, 9, 9, 9]));
+    }
+
+    #[test]
+    fn repeated_downloads_are_counted_once() {
+        let tracker = DownloadTracker::new(Duration::from_secs(60));
+
+        assert!(tracker.should_count("ip:127.0.0.1", 1));
+        assert!(!tracker.should_count("ip:127.0.0.1", 1));
+        assert!(tracker.should_count("ip:127.0.0.1", 2));
+        assert!(tracker.should_count("ip:127.0.0.2", 1));
+    }
+
+    #[test]
+    fn downloads_are_counted_again_after_the_window() {
+        let tracker = DownloadTracker::new(Duration::ZERO);
+
+        assert!(tracker.should_count("ip:127.0.0.1", 1));
+        assert!(tracker.should_count("ip:127.0.0.1", 1));
+        assert_eq!(tracker.recent.lock().order.len(), 1);
+    }
+
+    #[test]
+    fn oldest_downloads_are_forgotten_past_capacity() {
+        let tracker = DownloadTracker::with_capacity(Duration::from_secs(60), 2);
+
+        assert!(tracker.should_count("ip:127.0.0.1", 1));
+        assert!(tracker.should_count("ip:127.0.0.2", 1));
+        assert!(tracker.should_count("ip:127.0.0.3", 1));
+
+        assert_eq!(tracker.recent.lock().times.len(), 2);
+        assert!(tracker.should_count("ip:127.0.0.1", 1));
+        assert!(!tracker.should_count("ip:127.0.0.3", 1));
+    }
+}
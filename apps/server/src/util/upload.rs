@@ -0,0 +1,98 @@
+use crate::util::hash::{FileHasher, FileHashes};
+use app_core::{AppError, Result};
+use axum::extract::{multipart::Field, DefaultBodyLimit};
+use std::path::Path;
+use tempfile::NamedTempFile;
+use tokio::{fs::File, io::AsyncWriteExt};
+
+/// The most bytes a single text field in an upload can have.
+pub const MAX_TEXT_FIELD_SIZE: u64 = 64 * 1024;
+
+/// The most files a single version upload can have.
+pub const MAX_UPLOAD_FILES: usize = 16;
+
+/// How many bytes an upload's body can have on top of its files,
+/// for its text fields and the multipart framing around them.
+pub const UPLOAD_TEXT_ALLOWANCE: u64 = 1024 * 1024;
+
+/// Get the body limit for an upload of up to `files` files, each up to
+/// `file_limit` bytes, along with its text fields.
+pub fn upload_body_limit(file_limit: u64, files: usize) -> DefaultBodyLimit {
+    let limit = file_limit
+        .saturating_mul(files as u64)
+        .saturating_add(UPLOAD_TEXT_ALLOWANCE);
+
+    DefaultBodyLimit::max(usize::try_from(limit).unwrap_or(usize::MAX))
+}
+
+/// A file received from a multipart upload.
+/// The file is kept in a temporary file until it is dropped.
+#[derive(Debug)]
+pub struct UploadedFile {
+    /// The name the client gave the file, if any.
+    pub file_name: Option<String>,
+
+    /// The file's hashes and size.
+    pub hashes: FileHashes,
+
+    temp: NamedTempFile,
+}
+
+impl UploadedFile {
+    /// Get the path of the temporary file holding the upload.
+    pub fn path(&self) -> &Path {
+        self.temp.path()
+    }
+}
+
+/// Receive a file from a multipart field, writing it to a temporary file and
+/// hashing it as it comes in. Fails with [`AppError::PayloadTooLarge`] as soon
+/// as the file goes over `limit` bytes.
+pub async fn receive_file(mut field: Field<'_>, limit: u64) -> Result<UploadedFile> {
+    let file_name = field.file_name().map(|v| v.to_string());
+    let temp = NamedTempFile::new()?;
+    let mut file = File::from_std(temp.reopen()?);
+    let mut hasher = FileHasher::new();
+    let mut size = 0;
+
+    while let Some(chunk) = field.chunk().await? {
+        size += chunk.len() as u64;
+
+        if size > limit {
+            return Err(AppError::PayloadTooLarge(limit));
+        }
+
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+    }
+
+    file.flush().await?;
+
+    Ok(UploadedFile {
+        file_name,
+        hashes: hasher.finish(),
+        temp,
+    })
+}
+
+/// Receive a text field from a multipart upload. Fails with
+/// [`AppError::PayloadTooLarge`] as soon as the text goes over
+/// [`MAX_TEXT_FIELD_SIZE`] bytes.
+pub async fn receive_text(mut field: Field<'_>) -> Result<String> {
+    let mut data = Vec::new();
+
+    while let Some(chunk) = field.chunk().await? {
+        if (data.len() + chunk.len()) as u64 > MAX_TEXT_FIELD_SIZE {
+            return Err(AppError::PayloadTooLarge(MAX_TEXT_FIELD_SIZE));
+        }
+
+        data.extend_from_slice(&chunk);
+    }
+
+    String::from_utf8(data).map_err(|_| {
+        AppError::BadRequest(format!(
+            "The `{}` field isn't valid UTF-8!",
+            field.name().unwrap_or_default()
+        ))
+    })
+}
@@ -1,25 +1,160 @@
-use anyhow::anyhow;
-use app_core::Result;
-use db::{package_versions, DbConn, PackageVersion};
+use app_core::{AppError, Result};
+use db::{package_versions, DbConn, PackageVersion, VersionChannel};
 use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
-use semver::Version;
+use semver::{Version, VersionReq};
+use std::cmp::Ordering;
 
-pub async fn get_latest_version(pkg: i32, conn: &mut DbConn) -> Result<PackageVersion> {
+/// Leniently parse a version number.
+/// Mod versions are rarely strict semver ("1.2", "v2.0.1", "1.2.3.4", etc.),
+/// so this accepts anything `lenient_semver` can make sense of.
+pub fn parse_version(version: impl AsRef<str>) -> Option<Version> {
+    lenient_semver::parse(version.as_ref().trim()).ok()
+}
+
+/// Check that a version number can be parsed, returning the parsed version.
+pub fn validate_version(version: impl AsRef<str>) -> std::result::Result<Version, String> {
+    let version = version.as_ref();
+
+    parse_version(version).ok_or_else(|| format!("Invalid version number: '{}'", version))
+}
+
+/// Parse a version range, like `^1.2` or `>=1.0, <2.0`.
+pub fn parse_range(range: impl AsRef<str>) -> std::result::Result<VersionReq, String> {
+    let range = range.as_ref();
+
+    VersionReq::parse(range.trim()).map_err(|_| format!("Invalid version range: '{}'", range))
+}
+
+/// Check if a version number is in a range.
+/// Version numbers that can't be parsed are never in a range.
+pub fn matches_range(range: &VersionReq, version: impl AsRef<str>) -> bool {
+    parse_version(version)
+        .map(|v| range.matches(&v))
+        .unwrap_or(false)
+}
+
+/// Compare two version numbers.
+/// Unparsable versions sort before parsable ones, and are compared as strings.
+pub fn compare_versions(a: impl AsRef<str>, b: impl AsRef<str>) -> Ordering {
+    let (a, b) = (a.as_ref(), b.as_ref());
+
+    match (parse_version(a), parse_version(b)) {
+        (Some(x), Some(y)) => x.cmp(&y).then_with(|| a.cmp(b)),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Sort package versions from oldest to newest.
+pub fn sort_versions(versions: &mut [PackageVersion]) {
+    versions.sort_by(|a, b| compare_versions(&a.version_number, &b.version_number));
+}
+
+/// Get the latest version of a package on a release channel (including more stable channels).
+/// If no channel is given, this is the latest release, or the latest version on any channel
+/// if the package has no releases. Yanked versions are never picked.
+pub async fn get_latest_version(
+    pkg: i32,
+    channel: Option<VersionChannel>,
+    conn: &mut DbConn,
+) -> Result<PackageVersion> {
     let mut versions = package_versions::table
         .filter(package_versions::package.eq(pkg))
+        .filter(package_versions::yanked.eq(false))
         .select(PackageVersion::as_select())
         .load(conn)
         .await?;
 
-    versions.sort_by(|a, b| {
-        Version::parse(&a.version_number)
-            .unwrap()
-            .cmp(&Version::parse(&b.version_number).unwrap())
-    });
+    sort_versions(&mut versions);
+
+    let latest = versions
+        .iter()
+        .rev()
+        .find(|v| channel.unwrap_or_default().includes(v.channel));
+
+    match (latest, channel) {
+        (Some(ver), _) => Ok(ver.clone()),
+        (None, None) => versions.last().cloned().ok_or(AppError::NotFound),
+        (None, Some(_)) => Err(AppError::NotFound),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_odd_versions() {
+        for ver in [
+            "1.0",
+            "2",
+            "v1.2.3",
+            " 1.2.3 ",
+            "1.2.3.4",
+            "1.0.0-SNAPSHOT",
+            "2.0.0-beta.3",
+            "1.0.0+build.5",
+            "1.20.1-47.1.0",
+        ] {
+            assert!(parse_version(ver).is_some(), "failed to parse '{}'", ver);
+        }
+
+        assert_eq!(parse_version("v1.2").unwrap(), Version::new(1, 2, 0));
+        assert!(validate_version("").is_err());
+        assert!(validate_version("latest").is_err());
+    }
+
+    #[test]
+    fn orders_versions() {
+        let mut vers = vec![
+            "1.10.0",
+            "1.9",
+            "v1.2.3",
+            "oops",
+            "1.2.3-beta.1",
+            "2.0.0-alpha",
+            "1.2.3.4",
+        ];
+
+        vers.sort_by(|a, b| compare_versions(a, b));
+
+        assert_eq!(
+            vers,
+            vec![
+                "oops",
+                "1.2.3-beta.1",
+                "v1.2.3",
+                "1.2.3.4",
+                "1.9",
+                "1.10.0",
+                "2.0.0-alpha"
+            ]
+        );
+    }
+
+    #[test]
+    fn compares_consistently() {
+        assert_eq!(compare_versions("1.0", "1.0.0"), "1.0".cmp("1.0.0"));
+        assert_eq!(compare_versions("1.0.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("abc", "1.0"), Ordering::Less);
+    }
+
+    #[test]
+    fn matches_ranges() {
+        let range = parse_range("^1.2").unwrap();
+
+        assert!(matches_range(&range, "1.2"));
+        assert!(matches_range(&range, "v1.5.0"));
+        assert!(!matches_range(&range, "2.0.0"));
+        assert!(!matches_range(&range, "1.1.9"));
+        assert!(!matches_range(&range, "not a version"));
+
+        let range = parse_range(">=1.0, <2.0").unwrap();
 
-    versions
-        .last()
-        .cloned()
-        .ok_or(anyhow!("Could not find latest version!").into())
+        assert!(matches_range(&range, "1.99"));
+        assert!(!matches_range(&range, "2"));
+        assert!(parse_range("^^1").is_err());
+    }
 }
@@ -0,0 +1,78 @@
+//! Package verification.
+
+use db::PackageManifest;
+use std::path::Path;
+
+/// A report from verifying an uploaded package file.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+)]
+pub struct ValidationReport {
+    /// Problems that make the package invalid.
+    pub errors: Vec<String>,
+
+    /// Problems that don't stop the package from being uploaded.
+    pub warnings: Vec<String>,
+
+    /// The package's manifest, if one was found and parsed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<PackageManifest>,
+}
+
+impl ValidationReport {
+    /// Create an empty (valid) report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an error to this report.
+    pub fn error(mut self, msg: impl Into<String>) -> Self {
+        self.errors.push(msg.into());
+        self
+    }
+
+    /// Add a warning to this report.
+    pub fn warn(mut self, msg: impl Into<String>) -> Self {
+        self.warnings.push(msg.into());
+        self
+    }
+
+    /// Set the parsed manifest for this report.
+    pub fn with_manifest(mut self, manifest: PackageManifest) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+
+    /// Whether the package passed verification.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Something that can check uploaded package files.
+pub trait PackageVerifier: Send + Sync {
+    /// Verify a package file, returning a report of any problems found.
+    /// Uploads are kept on disk rather than in memory, so this gets the file's path.
+    fn verify(&self, file: &Path) -> ValidationReport;
+}
+
+/// A verifier that accepts every package.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopVerifier;
+
+impl PackageVerifier for NoopVerifier {
+    fn verify(&self, _file: &Path) -> ValidationReport {
+        ValidationReport::new()
+    }
+}
@@ -1,9 +1,27 @@
 use app_core::Result;
 use oauth2::{basic::BasicClient, AuthUrl, ClientId, ClientSecret, TokenUrl};
 
+/// Configuration for the login providers.
+/// Only the providers that are configured can be used to log in.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AuthConfigs {
-    pub github: AuthConfig,
+    #[serde(default)]
+    pub github: Option<AuthConfig>,
+
+    #[serde(default)]
+    pub gitlab: Option<GitLabAuthConfig>,
+
+    #[serde(default)]
+    pub discord: Option<AuthConfig>,
+
+    #[serde(default)]
+    pub oidc: Vec<OidcAuthConfig>,
+
+    /// The secret used to sign login states.
+    /// If this isn't set, a random one is made every time the server starts,
+    /// which won't work with multiple instances behind a load balancer.
+    #[serde(default)]
+    pub state_secret: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -12,15 +30,54 @@ pub struct AuthConfig {
     pub client_secret: String,
 }
 
-impl AuthConfigs {
-    pub fn github(&self) -> Result<BasicClient> {
-        Ok(BasicClient::new(
-            ClientId::new(self.github.client_id.clone()),
-            Some(ClientSecret::new(self.github.client_secret.clone())),
-            AuthUrl::new("https://github.com/login/oauth/authorize".to_string())?,
-            Some(TokenUrl::new(
-                "https://github.com/login/oauth/access_token".to_string(),
-            )?),
-        ))
-    }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabAuthConfig {
+    pub client_id: String,
+    pub client_secret: String,
+
+    /// The URL of the GitLab instance, for self-hosted ones.
+    #[serde(default = "default_gitlab_url")]
+    pub url: String,
+}
+
+/// A generic OpenID Connect provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcAuthConfig {
+    /// The provider's ID, used in login URLs. This must not change once people use it.
+    pub id: String,
+
+    /// The provider's display name.
+    pub name: String,
+
+    pub client_id: String,
+    pub client_secret: String,
+    pub auth_url: String,
+    pub token_url: String,
+    pub userinfo_url: String,
+
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+}
+
+fn default_gitlab_url() -> String {
+    "https://gitlab.com".into()
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec!["openid".into(), "profile".into(), "email".into()]
+}
+
+/// Create an OAuth2 client.
+pub fn oauth_client(
+    client_id: impl AsRef<str>,
+    client_secret: impl AsRef<str>,
+    auth_url: impl AsRef<str>,
+    token_url: impl AsRef<str>,
+) -> Result<BasicClient> {
+    Ok(BasicClient::new(
+        ClientId::new(client_id.as_ref().into()),
+        Some(ClientSecret::new(client_secret.as_ref().into())),
+        AuthUrl::new(auth_url.as_ref().into())?,
+        Some(TokenUrl::new(token_url.as_ref().into())?),
+    ))
 }
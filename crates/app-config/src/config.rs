@@ -1,4 +1,4 @@
-use crate::{AuthConfigs, MeilisearchConfig, PostgresConfig, StorageConfig, UIConfig};
+use crate::{AuthConfigs, JobsConfig, MeilisearchConfig, PostgresConfig, StorageConfig, UIConfig};
 use app_core::Result;
 use std::fs;
 
@@ -10,12 +10,27 @@ pub struct AppConfig {
     pub storage: StorageConfig,
     pub ui: UIConfig,
     pub meilisearch: MeilisearchConfig,
+
+    #[serde(default)]
+    pub jobs: JobsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+
+    #[serde(default)]
+    pub behind_proxy: bool,
+
+    #[serde(default = "default_download_window")]
+    pub download_window: u64,
+
+    #[serde(default = "default_max_package_size")]
+    pub max_package_size: u64,
+
+    #[serde(default = "default_max_image_size")]
+    pub max_image_size: u64,
 }
 
 impl Default for ServerConfig {
@@ -23,10 +38,26 @@ impl Default for ServerConfig {
         Self {
             host: "127.0.0.1".into(),
             port: 4000,
+            behind_proxy: false,
+            download_window: default_download_window(),
+            max_package_size: default_max_package_size(),
+            max_image_size: default_max_image_size(),
         }
     }
 }
 
+fn default_download_window() -> u64 {
+    3600
+}
+
+fn default_max_package_size() -> u64 {
+    100 * 1024 * 1024
+}
+
+fn default_max_image_size() -> u64 {
+    10 * 1024 * 1024
+}
+
 impl AppConfig {
     pub fn save(&self) -> Result<()> {
         fs::write("ModHost.toml", toml::to_string_pretty(self)?)?;
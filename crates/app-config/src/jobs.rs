@@ -0,0 +1,14 @@
+use std::collections::HashMap;
+
+/// Background job configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct JobsConfig {
+    /// How often each job runs (in seconds), by job name.
+    /// Jobs that aren't listed here run at their default interval.
+    #[serde(default)]
+    pub intervals: HashMap<String, u64>,
+
+    /// The names of jobs that shouldn't run at all.
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
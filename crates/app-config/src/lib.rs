@@ -4,6 +4,7 @@ extern crate serde;
 mod auth;
 mod config;
 mod db;
+mod jobs;
 mod meili;
 mod storage;
 mod ui;
@@ -12,6 +13,7 @@ mod util;
 pub use auth::*;
 pub use config::*;
 pub use db::*;
+pub use jobs::*;
 pub use meili::*;
 pub use storage::*;
 pub use ui::*;
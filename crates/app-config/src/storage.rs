@@ -1,12 +1,50 @@
 use app_core::Result;
 use s3::{creds::Credentials, Bucket, Region};
 
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackendKind {
+    #[default]
+    S3,
+    Fs,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadMode {
+    #[default]
+    Stream,
+    Redirect,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageConfig {
+    #[serde(default)]
+    pub backend: StorageBackendKind,
+
+    #[serde(default = "default_fs_path")]
+    pub fs_path: String,
+
+    #[serde(default)]
+    pub download_mode: DownloadMode,
+
+    #[serde(default = "default_presign_expiry")]
+    pub presign_expiry: u32,
+
+    #[serde(default)]
     pub s3_region: String,
+
+    #[serde(default)]
     pub s3_endpoint: String,
 
+    #[serde(default)]
     pub s3_access_key: String,
+
+    #[serde(default)]
     pub s3_secret_key: String,
 
     pub packages_bucket: String,
@@ -16,6 +54,10 @@ pub struct StorageConfig {
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
+            backend: StorageBackendKind::S3,
+            fs_path: default_fs_path(),
+            download_mode: DownloadMode::Stream,
+            presign_expiry: default_presign_expiry(),
             s3_region: String::new(),
             s3_endpoint: String::new(),
             s3_access_key: String::new(),
@@ -26,6 +68,14 @@ impl Default for StorageConfig {
     }
 }
 
+fn default_fs_path() -> String {
+    "storage".into()
+}
+
+fn default_presign_expiry() -> u32 {
+    300
+}
+
 impl StorageConfig {
     pub fn credentials(&self) -> Result<Credentials> {
         Ok(Credentials::new(
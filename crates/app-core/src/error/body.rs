@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{ToResponse, ToSchema};
+
+/// A stable code for the kind of error that happened.
+/// Clients should match on these instead of on error messages.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request was malformed or had an invalid field. (400)
+    BadRequest,
+
+    /// The request needs an auth token, and none was given. (401)
+    MissingToken,
+
+    /// The user isn't allowed to do this. (401)
+    Unauthorized,
+
+    /// The auth token doesn't have the scope needed to do this.
+    /// The details contain the missing scope. (403)
+    MissingScope,
+
+    /// The requested resource doesn't exist, or isn't visible to the user. (404)
+    NotFound,
+
+    /// The auth token doesn't belong to a known user. (404)
+    UnknownUser,
+
+    /// An upload is over one of the server's limits. The details contain
+    /// the size limit (`limit`) or the most files allowed (`max_files`). (413)
+    PayloadTooLarge,
+
+    /// An uploaded package failed verification.
+    /// The details contain the validation report. (422)
+    InvalidPackage,
+
+    /// Something went wrong on the server. (500)
+    InternalError,
+}
+
+/// The body of every error response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, ToResponse)]
+pub struct ErrorBody {
+    /// What kind of error this is.
+    pub code: ErrorCode,
+
+    /// A human-readable description of the error.
+    pub message: String,
+
+    /// Extra information about the error, depending on its code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<Value>,
+}
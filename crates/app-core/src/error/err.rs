@@ -1,7 +1,10 @@
 use std::num::ParseIntError;
 
-use super::{AxumError, HasCode};
-use axum::response::{IntoResponse, Response};
+use super::{AxumError, ErrorCode, HasCode};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
 use diesel::r2d2::PoolError as SyncPoolError;
 use diesel_async::pooled_connection::deadpool::{BuildError, PoolError};
 use jsglue::config::GlueConfigBuilderError;
@@ -113,15 +116,64 @@ pub enum AppError {
 
     #[error("Resource not found!")]
     NotFound,
+
+    #[error("Upload is too large! The limit is {0} bytes.")]
+    PayloadTooLarge(u64),
+
+    #[error("Too many files! The limit is {0} per upload.")]
+    TooManyFiles(usize),
+
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("You don't have permission to do that!")]
+    Unauthorized,
+
+    #[error("This token doesn't have the '{0}' scope!")]
+    MissingScope(String),
+
+    #[error("The package failed verification!")]
+    InvalidPackage(serde_json::Value),
 }
 
 impl HasCode for AppError {
     fn code(&self) -> u16 {
+        match self.error_code() {
+            ErrorCode::BadRequest => 400,
+            ErrorCode::MissingToken | ErrorCode::Unauthorized => 401,
+            ErrorCode::MissingScope => 403,
+            ErrorCode::NotFound | ErrorCode::UnknownUser => 404,
+            ErrorCode::PayloadTooLarge => 413,
+            ErrorCode::InvalidPackage => 422,
+            ErrorCode::InternalError => 500,
+        }
+    }
+
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Multipart(err) if err.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+                ErrorCode::PayloadTooLarge
+            }
+
+            Self::Multipart(_) | Self::ParseInt(_) | Self::BadRequest(_) => ErrorCode::BadRequest,
+            Self::MissingToken => ErrorCode::MissingToken,
+            Self::Unauthorized => ErrorCode::Unauthorized,
+            Self::MissingScope(_) => ErrorCode::MissingScope,
+            Self::NotFound | Self::Database(diesel::result::Error::NotFound) => ErrorCode::NotFound,
+            Self::UnknownUser => ErrorCode::UnknownUser,
+            Self::PayloadTooLarge(_) | Self::TooManyFiles(_) => ErrorCode::PayloadTooLarge,
+            Self::InvalidPackage(_) => ErrorCode::InvalidPackage,
+            _ => ErrorCode::InternalError,
+        }
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
         match self {
-            Self::Multipart(_) | Self::ParseInt(_) => 400,
-            Self::MissingToken => 401,
-            Self::NotFound | Self::UnknownUser => 404,
-            _ => 500,
+            Self::PayloadTooLarge(limit) => Some(serde_json::json!({ "limit": limit })),
+            Self::TooManyFiles(limit) => Some(serde_json::json!({ "max_files": limit })),
+            Self::InvalidPackage(report) => Some(report.clone()),
+            Self::MissingScope(scope) => Some(serde_json::json!({ "scope": scope })),
+            _ => None,
         }
     }
 }
@@ -1,6 +1,8 @@
+mod body;
 mod err;
 mod util;
 
+pub use body::*;
 pub use err::*;
 pub use util::*;
 
@@ -1,18 +1,45 @@
+use super::{ErrorBody, ErrorCode};
 use axum::{body::Body, response::Response};
+use serde_json::Value;
 use std::fmt::Display;
 
 pub trait HasCode {
+    /// The HTTP status code for this error.
     fn code(&self) -> u16;
+
+    /// The stable error code for this error.
+    fn error_code(&self) -> ErrorCode;
+
+    /// Extra information about this error.
+    fn details(&self) -> Option<Value> {
+        None
+    }
 }
 
 pub trait AxumError: Display
 where
     Self: Sized + HasCode,
 {
+    fn as_body(&self) -> ErrorBody {
+        ErrorBody {
+            code: self.error_code(),
+            message: self.to_string(),
+            details: self.details(),
+        }
+    }
+
     fn as_response(self) -> Response {
+        let body = match serde_json::to_string(&self.as_body()) {
+            Ok(it) => it,
+            Err(err) => {
+                return Response::new(Body::new(format!("Could not create a response: {}", err)))
+            }
+        };
+
         match Response::builder()
             .status(self.code())
-            .body(Body::new(format!("{}", self)))
+            .header("Content-Type", "application/json")
+            .body(Body::new(body))
         {
             Ok(it) => it,
             Err(err) => Response::new(Body::new(format!("Could not create a response: {}", err))),
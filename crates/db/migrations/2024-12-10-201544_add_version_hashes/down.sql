@@ -0,0 +1,4 @@
+ALTER TABLE package_versions DROP COLUMN IF EXISTS sha1;
+ALTER TABLE package_versions DROP COLUMN IF EXISTS sha256;
+ALTER TABLE package_versions DROP COLUMN IF EXISTS sha512;
+ALTER TABLE package_versions DROP COLUMN IF EXISTS file_size;
@@ -0,0 +1,9 @@
+ALTER TABLE package_versions ADD sha1 TEXT;
+ALTER TABLE package_versions ADD sha256 TEXT;
+ALTER TABLE package_versions ADD sha512 TEXT;
+ALTER TABLE package_versions ADD file_size BIGINT;
+
+-- Files were previously stored by their SHA-1 digest.
+UPDATE package_versions SET sha1 = file_id;
+
+ALTER TABLE package_versions ALTER COLUMN sha1 SET NOT NULL;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS daily_downloads (
+    package INTEGER NOT NULL REFERENCES packages(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL REFERENCES package_versions(id) ON DELETE CASCADE,
+    day DATE NOT NULL DEFAULT CURRENT_DATE,
+    downloads INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (version, day)
+);
+
+CREATE INDEX IF NOT EXISTS daily_downloads_package_day ON daily_downloads (package, day);
@@ -0,0 +1,3 @@
+ALTER TABLE package_versions DROP COLUMN IF EXISTS channel;
+
+DROP TYPE IF EXISTS Channel;
@@ -0,0 +1,3 @@
+CREATE TYPE Channel as ENUM ('release', 'beta', 'alpha');
+
+ALTER TABLE package_versions ADD channel Channel NOT NULL DEFAULT 'release';
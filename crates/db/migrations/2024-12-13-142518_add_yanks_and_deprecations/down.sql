@@ -0,0 +1,5 @@
+ALTER TABLE packages DROP COLUMN IF EXISTS replacement;
+ALTER TABLE packages DROP COLUMN IF EXISTS deprecation;
+
+ALTER TABLE package_versions DROP COLUMN IF EXISTS deprecation;
+ALTER TABLE package_versions DROP COLUMN IF EXISTS yanked;
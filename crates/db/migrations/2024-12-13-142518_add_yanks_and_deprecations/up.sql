@@ -0,0 +1,5 @@
+ALTER TABLE package_versions ADD yanked BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE package_versions ADD deprecation TEXT;
+
+ALTER TABLE packages ADD deprecation TEXT;
+ALTER TABLE packages ADD replacement INTEGER REFERENCES packages(id) ON DELETE SET NULL;
@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS version_files (
+    id SERIAL NOT NULL PRIMARY KEY,
+    version INTEGER NOT NULL REFERENCES package_versions(id) ON DELETE CASCADE,
+    file_id TEXT NOT NULL,
+    file_name TEXT,
+    size BIGINT,
+    sha1 TEXT NOT NULL,
+    sha256 TEXT,
+    sha512 TEXT,
+    is_primary BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS version_files_version ON version_files (version);
+CREATE INDEX IF NOT EXISTS version_files_file_id ON version_files (file_id);
+CREATE UNIQUE INDEX IF NOT EXISTS version_files_primary ON version_files (version) WHERE is_primary;
+
+INSERT INTO version_files (version, file_id, size, sha1, sha256, sha512, is_primary, created_at)
+SELECT id, file_id, file_size, sha1, sha256, sha512, TRUE, created_at FROM package_versions;
@@ -0,0 +1,14 @@
+CREATE TYPE token_scope AS ENUM ('read', 'publish', 'manage_authors', 'admin');
+
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id SERIAL NOT NULL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL UNIQUE,
+    scopes token_scope[] NOT NULL DEFAULT '{}',
+    package INTEGER REFERENCES packages(id) ON DELETE CASCADE,
+    expires TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS api_tokens_user_id ON api_tokens (user_id);
@@ -0,0 +1,16 @@
+-- Hashed tokens can't be turned back into usable ones, so everyone has to log in again.
+DELETE FROM user_tokens;
+DELETE FROM api_tokens;
+
+ALTER TABLE api_tokens RENAME COLUMN hash TO value;
+ALTER TABLE api_tokens DROP COLUMN last_ip;
+ALTER TABLE api_tokens DROP COLUMN last_used;
+ALTER TABLE api_tokens DROP COLUMN prefix;
+
+DROP INDEX IF EXISTS user_tokens_user_id;
+DROP INDEX IF EXISTS user_tokens_hash;
+ALTER TABLE user_tokens RENAME COLUMN hash TO value;
+ALTER TABLE user_tokens DROP COLUMN last_ip;
+ALTER TABLE user_tokens DROP COLUMN last_used;
+ALTER TABLE user_tokens DROP COLUMN created_at;
+ALTER TABLE user_tokens DROP COLUMN prefix;
@@ -0,0 +1,15 @@
+-- Tokens are stored as SHA-256 hashes, along with a short prefix so people can tell them apart.
+ALTER TABLE user_tokens ADD prefix TEXT NOT NULL DEFAULT '';
+ALTER TABLE user_tokens ADD created_at TIMESTAMP NOT NULL DEFAULT NOW();
+ALTER TABLE user_tokens ADD last_used TIMESTAMP;
+ALTER TABLE user_tokens ADD last_ip TEXT;
+UPDATE user_tokens SET prefix = LEFT(value, 12), value = encode(sha256(value::bytea), 'hex');
+ALTER TABLE user_tokens RENAME COLUMN value TO hash;
+CREATE UNIQUE INDEX IF NOT EXISTS user_tokens_hash ON user_tokens (hash);
+CREATE INDEX IF NOT EXISTS user_tokens_user_id ON user_tokens (user_id);
+
+ALTER TABLE api_tokens ADD prefix TEXT NOT NULL DEFAULT '';
+ALTER TABLE api_tokens ADD last_used TIMESTAMP;
+ALTER TABLE api_tokens ADD last_ip TEXT;
+UPDATE api_tokens SET prefix = LEFT(value, 12), value = encode(sha256(value::bytea), 'hex');
+ALTER TABLE api_tokens RENAME COLUMN value TO hash;
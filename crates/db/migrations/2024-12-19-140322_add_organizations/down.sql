@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS packages_organization;
+ALTER TABLE packages DROP COLUMN IF EXISTS organization;
+DROP TABLE IF EXISTS organization_members;
+DROP TABLE IF EXISTS organizations;
+DROP TYPE IF EXISTS org_role;
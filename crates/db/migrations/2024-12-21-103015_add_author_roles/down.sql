@@ -0,0 +1,2 @@
+ALTER TABLE package_authors DROP COLUMN IF EXISTS role;
+DROP TYPE IF EXISTS author_role;
@@ -0,0 +1,4 @@
+CREATE TYPE author_role AS ENUM ('gallery_editor', 'uploader', 'maintainer', 'owner');
+
+-- Every existing author had full control, so they all start out as owners.
+ALTER TABLE package_authors ADD role author_role NOT NULL DEFAULT 'owner';
@@ -0,0 +1,6 @@
+-- Users who share a username with an older account get their ID added to it.
+UPDATE users SET username = users.username || '-' || users.id
+FROM users AS older
+WHERE older.username = users.username AND older.id < users.id;
+
+ALTER TABLE users ADD CONSTRAINT users_username_key UNIQUE (username);
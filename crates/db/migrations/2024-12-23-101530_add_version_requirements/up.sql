@@ -0,0 +1,11 @@
+-- What each version's manifest declared, so its relations can be resolved again
+-- later instead of staying pinned to the versions that existed at upload time.
+-- A requirement of NULL matches any version of the package.
+CREATE TABLE IF NOT EXISTS version_requirements (
+    id SERIAL NOT NULL PRIMARY KEY,
+    version INTEGER NOT NULL REFERENCES package_versions(id) ON DELETE CASCADE,
+    package INTEGER NOT NULL REFERENCES packages(id) ON DELETE CASCADE,
+    kind INTEGER NOT NULL,
+    requirement TEXT,
+    UNIQUE (version, package, kind)
+);
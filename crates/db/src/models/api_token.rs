@@ -0,0 +1,184 @@
+use chrono::NaiveDateTime;
+use diesel::pg::Pg;
+use diesel_derive_enum::DbEnum;
+
+use crate::{api_tokens, Package, User};
+
+/// Something a personal API token is allowed to do.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+    DbEnum,
+)]
+#[ExistingTypePath = "crate::schema::sql_types::TokenScope"]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// Read packages and versions, including private ones the user can see.
+    Read,
+
+    /// Create and update packages, versions, and gallery images.
+    Publish,
+
+    /// Add and remove package authors.
+    ManageAuthors,
+
+    /// Use the user's admin permissions (if they have them).
+    Admin,
+}
+
+/// A named personal API token.
+/// Unlike login tokens, these have scopes, can be restricted to one package,
+/// and only expire if the user asks them to.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Identifiable,
+    Queryable,
+    Selectable,
+    Associations,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = api_tokens)]
+#[diesel(belongs_to(User))]
+#[diesel(belongs_to(Package, foreign_key = package))]
+#[diesel(check_for_backend(Pg))]
+pub struct ApiToken {
+    /// The token's ID.
+    pub id: i32,
+
+    /// The ID of the user this token belongs to.
+    pub user_id: i32,
+
+    /// The token's name.
+    pub name: String,
+
+    /// The SHA-256 hash of the token's value.
+    /// The value itself is never stored, and is only shown once, when the token is created.
+    #[serde(skip)]
+    pub hash: String,
+
+    /// The first few characters of the token's value, to tell it apart from others.
+    pub prefix: String,
+
+    /// What the token is allowed to do.
+    pub scopes: Vec<TokenScope>,
+
+    /// The ID of the package this token is restricted to, if any.
+    pub package: Option<i32>,
+
+    /// The token's expiration date, if it has one.
+    pub expires: Option<NaiveDateTime>,
+
+    /// When the token was created.
+    pub created_at: NaiveDateTime,
+
+    /// When the token was last used, if it has been.
+    pub last_used: Option<NaiveDateTime>,
+
+    /// The IP address the token was last used from, if it's known.
+    pub last_ip: Option<String>,
+}
+
+/// A model for creating a new personal API token in the database.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Insertable,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = api_tokens)]
+#[diesel(check_for_backend(Pg))]
+pub struct NewApiToken {
+    /// The ID of the user this token belongs to.
+    pub user_id: i32,
+
+    /// The token's name.
+    pub name: String,
+
+    /// The SHA-256 hash of the token's value.
+    pub hash: String,
+
+    /// The first few characters of the token's value.
+    pub prefix: String,
+
+    /// What the token is allowed to do.
+    pub scopes: Vec<TokenScope>,
+
+    /// The ID of the package this token is restricted to, if any.
+    pub package: Option<i32>,
+
+    /// The token's expiration date, if it has one.
+    pub expires: Option<NaiveDateTime>,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Publish => "publish",
+            Self::ManageAuthors => "manage_authors",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Get every scope.
+    pub fn all() -> Vec<Self> {
+        vec![Self::Read, Self::Publish, Self::ManageAuthors, Self::Admin]
+    }
+
+    /// Whether a token with this scope can do something that needs another scope.
+    /// Every scope can also read.
+    pub fn allows(&self, other: TokenScope) -> bool {
+        *self == other || other == Self::Read
+    }
+}
+
+impl ApiToken {
+    /// Whether this token has expired.
+    pub fn is_expired(&self) -> bool {
+        self.expires
+            .is_some_and(|it| it <= chrono::Utc::now().naive_utc())
+    }
+
+    /// Whether this token can do something that needs a scope,
+    /// optionally on a specific package.
+    /// Tokens restricted to a package can only be used for requests about that
+    /// package, so they can't be used to search or list other (private) packages.
+    pub fn allows(&self, scope: TokenScope, package: Option<i32>) -> bool {
+        let scoped = self.scopes.iter().any(|it| it.allows(scope));
+
+        let in_package = match (self.package, package) {
+            (Some(allowed), Some(pkg)) => allowed == pkg,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+
+        scoped && in_package
+    }
+}
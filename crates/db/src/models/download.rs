@@ -0,0 +1,40 @@
+use chrono::NaiveDate;
+use diesel::pg::Pg;
+
+use crate::{daily_downloads, Package, PackageVersion};
+
+/// The number of times a package version was downloaded on a day.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Queryable,
+    Selectable,
+    Insertable,
+    Associations,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = daily_downloads)]
+#[diesel(belongs_to(Package, foreign_key = package))]
+#[diesel(belongs_to(PackageVersion, foreign_key = version))]
+#[diesel(check_for_backend(Pg))]
+pub struct DailyDownloads {
+    /// The package ID.
+    pub package: i32,
+
+    /// The package version ID.
+    pub version: i32,
+
+    /// The day these downloads happened on (in UTC).
+    pub day: NaiveDate,
+
+    /// The number of downloads.
+    pub downloads: i32,
+}
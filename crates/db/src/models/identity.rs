@@ -0,0 +1,77 @@
+use chrono::NaiveDateTime;
+use diesel::pg::Pg;
+
+use crate::{user_identities, User};
+
+/// A login provider account linked to a user.
+/// A user can have any number of these, but only one per provider account.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Identifiable,
+    Queryable,
+    Selectable,
+    Associations,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = user_identities)]
+#[diesel(belongs_to(User))]
+#[diesel(check_for_backend(Pg))]
+pub struct UserIdentity {
+    /// The identity's ID.
+    pub id: i32,
+
+    /// The ID of the user this identity belongs to.
+    pub user_id: i32,
+
+    /// The login provider's ID (like `github` or `discord`).
+    pub provider: String,
+
+    /// The account's ID on the provider.
+    pub provider_id: String,
+
+    /// The account's username on the provider, as of the last login.
+    pub username: String,
+
+    /// When the identity was linked.
+    pub created_at: NaiveDateTime,
+}
+
+/// A model for linking a new identity to a user in the database.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Insertable,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = user_identities)]
+#[diesel(check_for_backend(Pg))]
+pub struct NewUserIdentity {
+    /// The ID of the user this identity belongs to.
+    pub user_id: i32,
+
+    /// The login provider's ID.
+    pub provider: String,
+
+    /// The account's ID on the provider.
+    pub provider_id: String,
+
+    /// The account's username on the provider.
+    pub username: String,
+}
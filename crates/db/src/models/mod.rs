@@ -1,15 +1,25 @@
+mod api_token;
+mod download;
 mod gallery;
+mod identity;
 mod manifest;
+mod org;
 mod pkg;
 mod pkg_author;
 mod pkg_relation;
 mod pkg_ver;
 mod user;
+mod ver_file;
 
+pub use api_token::*;
+pub use download::*;
 pub use gallery::*;
+pub use identity::*;
 pub use manifest::*;
+pub use org::*;
 pub use pkg::*;
 pub use pkg_author::*;
 pub use pkg_relation::*;
 pub use pkg_ver::*;
 pub use user::*;
+pub use ver_file::*;
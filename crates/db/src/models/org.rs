@@ -0,0 +1,177 @@
+use chrono::NaiveDateTime;
+use diesel::pg::Pg;
+use diesel_derive_enum::DbEnum;
+
+use crate::{organization_members, organizations, User};
+
+/// A member's role in an organization.
+/// Each role can do everything the roles before it can.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+    DbEnum,
+    Default,
+)]
+#[ExistingTypePath = "crate::schema::sql_types::OrgRole"]
+#[serde(rename_all = "snake_case")]
+pub enum OrgRole {
+    /// Can see and work on the organization's packages.
+    #[default]
+    Member,
+
+    /// Can also manage the organization's members, create and delete its packages,
+    /// and change their authors.
+    Admin,
+
+    /// Can also manage admins and owners, and edit or delete the organization.
+    Owner,
+}
+
+/// An organization (a team) that can own packages.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Identifiable,
+    Queryable,
+    Selectable,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = organizations)]
+#[diesel(check_for_backend(Pg))]
+pub struct Organization {
+    /// The organization's ID.
+    pub id: i32,
+
+    /// The organization's name.
+    pub name: String,
+
+    /// The organization's URL slug.
+    pub slug: String,
+
+    /// A short description of the organization.
+    pub description: String,
+
+    /// The date the organization was created.
+    pub created_at: NaiveDateTime,
+
+    /// The date the organization was last updated.
+    pub updated_at: NaiveDateTime,
+}
+
+/// A model for creating a new organization.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Insertable,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = organizations)]
+#[diesel(check_for_backend(Pg))]
+pub struct NewOrganization {
+    /// The organization's URL slug.
+    pub slug: String,
+
+    /// The organization's name.
+    pub name: String,
+
+    /// A short description of the organization.
+    #[serde(default)]
+    pub description: String,
+}
+
+/// A user's membership in an organization.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Identifiable,
+    Queryable,
+    Selectable,
+    Insertable,
+    Associations,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = organization_members)]
+#[diesel(belongs_to(Organization, foreign_key = organization))]
+#[diesel(belongs_to(User))]
+#[diesel(check_for_backend(Pg))]
+#[diesel(primary_key(organization, user_id))]
+pub struct OrganizationMember {
+    /// The organization ID.
+    pub organization: i32,
+
+    /// The user ID.
+    pub user_id: i32,
+
+    /// The user's role in the organization.
+    pub role: OrgRole,
+}
+
+/// A member of an organization, with their user info.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct OrgMember {
+    /// The user.
+    #[serde(flatten)]
+    pub user: User,
+
+    /// The user's role in the organization.
+    pub role: OrgRole,
+}
+
+/// An organization with its members.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct OrganizationData {
+    /// The organization.
+    #[serde(flatten)]
+    pub org: Organization,
+
+    /// The organization's members.
+    pub members: Vec<OrgMember>,
+}
+
+impl OrgRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Member => "member",
+            Self::Admin => "admin",
+            Self::Owner => "owner",
+        }
+    }
+}
@@ -90,6 +90,15 @@ pub struct Package {
 
     /// A list of tags for this package.
     pub tags: Vec<Option<String>>,
+
+    /// A deprecation message, if this package is deprecated.
+    pub deprecation: Option<String>,
+
+    /// The ID of the package that replaces this one, if it is deprecated.
+    pub replacement: Option<i32>,
+
+    /// The ID of the organization that owns this package, if one does.
+    pub organization: Option<i32>,
 }
 
 /// A model for creating a new package.
@@ -147,6 +156,10 @@ pub struct NewPackage {
     /// A list of tags for this package.
     #[serde(default)]
     pub tags: Vec<Option<String>>,
+
+    /// The ID of the organization that should own the package, if one should.
+    #[serde(default)]
+    pub organization: Option<i32>,
 }
 
 /// A package with additional data.
@@ -198,6 +211,18 @@ pub struct PackageData {
 
     /// A list of tags for this package.
     pub tags: Vec<String>,
+
+    /// A deprecation message, if this package is deprecated.
+    #[serde(default)]
+    pub deprecation: Option<String>,
+
+    /// The ID of the package that replaces this one, if it is deprecated.
+    #[serde(default)]
+    pub replacement: Option<i32>,
+
+    /// The ID of the organization that owns this package, if one does.
+    #[serde(default)]
+    pub organization: Option<i32>,
 }
 
 impl Package {
@@ -217,6 +242,9 @@ impl Package {
             visibility: self.visibility,
             license: self.license,
             tags: self.tags.into_iter().filter_map(|v| v).collect_vec(),
+            deprecation: self.deprecation,
+            replacement: self.replacement,
+            organization: self.organization,
             authors,
         }
     }
@@ -1,8 +1,66 @@
 use crate::{
     models::{pkg::Package, user::User},
     schema::package_authors,
+    OrgRole, TokenScope,
 };
 use diesel::pg::Pg;
+use diesel_derive_enum::DbEnum;
+
+/// An author's role in a package.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+    DbEnum,
+    Default,
+)]
+#[ExistingTypePath = "crate::schema::sql_types::AuthorRole"]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorRole {
+    /// Can manage the package's gallery images.
+    GalleryEditor,
+
+    /// Can upload, edit, and delete versions.
+    Uploader,
+
+    /// Can do everything but delete or transfer the package and manage its owners.
+    #[default]
+    Maintainer,
+
+    /// Can do everything.
+    Owner,
+}
+
+/// Something that can be done to a package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PackagePermission {
+    /// See the package, even if it's private.
+    View,
+
+    /// Upload, edit, and delete versions.
+    Upload,
+
+    /// Add, edit, and remove gallery images.
+    Gallery,
+
+    /// Edit the package's info.
+    Edit,
+
+    /// Add and remove authors, and change their roles.
+    ManageAuthors,
+
+    /// Delete or transfer the package, and manage its owners.
+    Manage,
+}
 
 /// A package author.
 #[derive(
@@ -34,4 +92,92 @@ pub struct PackageAuthor {
 
     /// The user ID.
     pub user_id: i32,
+
+    /// The author's role in the package.
+    pub role: AuthorRole,
+}
+
+/// An author of a package, with their user info.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct AuthorData {
+    /// The user.
+    #[serde(flatten)]
+    pub user: User,
+
+    /// The author's role in the package.
+    pub role: AuthorRole,
+}
+
+/// What a user can do to a package, from their author role, their role in
+/// the organization that owns it, and whether they're an admin.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PackageAccess {
+    /// Whether the user is a site admin.
+    pub admin: bool,
+
+    /// The user's author role in the package, if they're an author.
+    pub author: Option<AuthorRole>,
+
+    /// The user's role in the organization that owns the package, if any.
+    pub org: Option<OrgRole>,
+}
+
+impl PackageAccess {
+    /// Check if this access lets the user do something.
+    pub fn allows(&self, perm: PackagePermission) -> bool {
+        self.admin
+            || self.author.is_some_and(|it| it.allows(perm))
+            || self.org.is_some_and(|it| it.allows(perm))
+    }
+}
+
+impl AuthorRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GalleryEditor => "gallery_editor",
+            Self::Uploader => "uploader",
+            Self::Maintainer => "maintainer",
+            Self::Owner => "owner",
+        }
+    }
+
+    /// Check if an author with this role can do something.
+    pub fn allows(&self, perm: PackagePermission) -> bool {
+        match self {
+            Self::Owner => true,
+            Self::Maintainer => perm != PackagePermission::Manage,
+
+            Self::Uploader => matches!(perm, PackagePermission::View | PackagePermission::Upload),
+
+            Self::GalleryEditor => {
+                matches!(perm, PackagePermission::View | PackagePermission::Gallery)
+            }
+        }
+    }
+}
+
+impl OrgRole {
+    /// Check if a member of the organization that owns a package can do something to it.
+    pub fn allows(&self, perm: PackagePermission) -> bool {
+        match self {
+            Self::Owner | Self::Admin => true,
+            Self::Member => !matches!(
+                perm,
+                PackagePermission::ManageAuthors | PackagePermission::Manage
+            ),
+        }
+    }
+}
+
+impl PackagePermission {
+    /// The scope a personal API token needs to do this.
+    pub fn scope(&self) -> TokenScope {
+        match self {
+            Self::View => TokenScope::Read,
+            Self::ManageAuthors => TokenScope::ManageAuthors,
+            Self::Upload | Self::Gallery | Self::Edit | Self::Manage => TokenScope::Publish,
+        }
+    }
 }
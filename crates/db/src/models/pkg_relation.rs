@@ -1,6 +1,9 @@
 use crate::{
-    models::pkg_ver::{PackageVersion, PackageVersionRef},
-    schema::package_relations,
+    models::{
+        pkg::Package,
+        pkg_ver::{PackageVersion, PackageVersionRef},
+    },
+    schema::{package_relations, version_requirements},
 };
 use diesel::{
     backend::Backend,
@@ -13,7 +16,6 @@ use diesel::{
     Queryable,
 };
 
-// TODO: Do something with this xD
 /// A relation between packages.
 #[derive(
     Debug,
@@ -48,6 +50,81 @@ pub struct PackageRelation {
     pub kind: RelationKind,
 }
 
+/// A package version's requirement on another package, as declared in its manifest.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Identifiable,
+    Queryable,
+    Selectable,
+    Associations,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = version_requirements)]
+#[diesel(belongs_to(PackageVersion, foreign_key = version))]
+#[diesel(belongs_to(Package, foreign_key = package))]
+#[diesel(check_for_backend(Pg))]
+pub struct VersionRequirement {
+    /// The requirement's ID.
+    pub id: i32,
+
+    /// The ID of the version that declared the requirement.
+    pub version: i32,
+
+    /// The ID of the package it's about.
+    pub package: i32,
+
+    /// The relation kind.
+    pub kind: RelationKind,
+
+    /// The versions of the package it matches, as an exact version number or
+    /// name, or a semver range. If this is missing, it matches every version.
+    pub requirement: Option<String>,
+}
+
+/// A model for creating a new version requirement in the database.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Insertable,
+)]
+#[diesel(table_name = version_requirements)]
+#[diesel(check_for_backend(Pg))]
+pub struct NewVersionRequirement {
+    /// The ID of the version that declared the requirement.
+    pub version: i32,
+
+    /// The ID of the package it's about.
+    pub package: i32,
+
+    /// The relation kind.
+    pub kind: RelationKind,
+
+    /// The versions of the package it matches.
+    pub requirement: Option<String>,
+}
+
+/// A resolved relation between package versions, used for the dependency graph endpoints.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+pub struct VersionRelation {
+    /// The relation kind.
+    pub kind: RelationKind,
+
+    /// The package on the other side of the relation.
+    pub package: Package,
+
+    /// The package version on the other side of the relation.
+    pub version: PackageVersion,
+}
+
 /// The kind of relation between packages.
 #[repr(i32)]
 #[derive(
@@ -2,8 +2,43 @@ use crate::{
     models::pkg::Package,
     schema::{package_version_refs, package_versions},
 };
+use app_core::AppError;
 use chrono::NaiveDateTime;
 use diesel::pg::Pg;
+use diesel_derive_enum::DbEnum;
+use std::str::FromStr;
+
+/// The release channel of a package version.
+/// Channels are ordered from most to least stable.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+    DbEnum,
+    Default,
+)]
+#[ExistingTypePath = "crate::schema::sql_types::Channel"]
+#[serde(rename_all = "snake_case")]
+pub enum VersionChannel {
+    #[default]
+    #[serde(alias = "Release")]
+    Release,
+
+    #[serde(alias = "Beta")]
+    Beta,
+
+    #[serde(alias = "Alpha")]
+    Alpha,
+}
 
 /// A package version.
 #[derive(
@@ -40,6 +75,7 @@ pub struct PackageVersion {
     pub version_number: String,
 
     /// The file ID in the bucket.
+    /// This is the SHA-256 hash of the file (or SHA-1 for older versions).
     #[serde(skip)]
     pub file_id: String,
 
@@ -60,6 +96,32 @@ pub struct PackageVersion {
 
     /// The number of downloads this version has.
     pub downloads: i32,
+
+    /// The SHA-1 hash of this version's file.
+    pub sha1: String,
+
+    /// The SHA-256 hash of this version's file.
+    /// This is missing for versions uploaded before it was recorded.
+    pub sha256: Option<String>,
+
+    /// The SHA-512 hash of this version's file.
+    /// This is missing for versions uploaded before it was recorded.
+    pub sha512: Option<String>,
+
+    /// The size of this version's file, in bytes.
+    /// This is missing for versions uploaded before it was recorded.
+    pub file_size: Option<i64>,
+
+    /// The release channel of this version.
+    pub channel: VersionChannel,
+
+    /// Whether this version has been yanked.
+    /// Yanked versions are hidden from listings and never picked as the latest
+    /// version, but can still be downloaded directly.
+    pub yanked: bool,
+
+    /// A deprecation message, if this version is deprecated.
+    pub deprecation: Option<String>,
 }
 
 /// The initial data for creating a new package version in the database.
@@ -107,6 +169,21 @@ pub struct NewPackageVersion {
 
     /// The number of downloads this version has.
     pub downloads: i32,
+
+    /// The SHA-1 hash of this version's file.
+    pub sha1: String,
+
+    /// The SHA-256 hash of this version's file.
+    pub sha256: Option<String>,
+
+    /// The SHA-512 hash of this version's file.
+    pub sha512: Option<String>,
+
+    /// The size of this version's file, in bytes.
+    pub file_size: Option<i64>,
+
+    /// The release channel of this version.
+    pub channel: VersionChannel,
 }
 
 /// A reference to a package version.
@@ -137,25 +214,65 @@ pub struct PackageVersionRef {
 
 /// The initial data for creating a new package version.
 /// This should be formatted as "multipart/form-data".
+/// Any fields left out are filled in from the package's manifest,
+/// and fields that conflict with the manifest are rejected.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, ToSchema, ToResponse)]
 pub struct PackageVersionInit {
-    /// The name of the package.
-    pub name: String,
+    /// The name of the version.
+    /// Defaults to the version number.
+    pub name: Option<String>,
 
     /// The version number.
-    pub version_number: String,
+    pub version_number: Option<String>,
 
     /// An optional changelog.
     pub changelog: Option<String>,
 
     /// A list of loaders this version works on.
     /// This should be a comma-separated list in the request.
-    pub loaders: String,
+    pub loaders: Option<String>,
 
     /// A list of game versions this works on.
     /// This should be a comma-separated list in the request.
-    pub game_versions: String,
+    pub game_versions: Option<String>,
+
+    /// The release channel. Defaults to release.
+    pub channel: Option<VersionChannel>,
 
     /// The file content.
+    /// This field can be repeated to upload extra files. The first one is the
+    /// version's primary file, and the others are named by their file names.
     pub file: Vec<u8>,
 }
+
+impl VersionChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Release => "release",
+            Self::Beta => "beta",
+            Self::Alpha => "alpha",
+        }
+    }
+
+    /// Whether a version on this channel should be shown to users of another channel.
+    /// Users of a channel see versions on that channel and any more stable ones.
+    pub fn includes(&self, other: VersionChannel) -> bool {
+        other <= *self
+    }
+}
+
+impl FromStr for VersionChannel {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "release" => Ok(Self::Release),
+            "beta" => Ok(Self::Beta),
+            "alpha" => Ok(Self::Alpha),
+            other => Err(AppError::BadRequest(format!(
+                "Unknown release channel: {}",
+                other
+            ))),
+        }
+    }
+}
@@ -28,7 +28,7 @@ pub struct User {
     /// The user's username.
     pub username: String,
 
-    /// The user's GitHub ID.
+    /// The user's GitHub ID, or -1 if they haven't linked a GitHub account.
     pub github_id: i32,
 
     /// Are they an admin?
@@ -62,7 +62,7 @@ pub struct NewUser {
     pub github_id: i32,
 }
 
-/// A user's token.
+/// A user's login token (a session).
 #[derive(
     Debug,
     Clone,
@@ -90,11 +90,25 @@ pub struct UserToken {
     /// The user's ID.
     pub user_id: i32,
 
-    /// The token's value.
-    pub value: String,
+    /// The SHA-256 hash of the token's value.
+    /// The value itself is never stored.
+    #[serde(skip)]
+    pub hash: String,
+
+    /// The first few characters of the token's value, to tell it apart from others.
+    pub prefix: String,
 
     /// The token's expiration date.
     pub expires: NaiveDateTime,
+
+    /// When the token was created.
+    pub created_at: NaiveDateTime,
+
+    /// When the token was last used, if it has been.
+    pub last_used: Option<NaiveDateTime>,
+
+    /// The IP address the token was last used from, if it's known.
+    pub last_ip: Option<String>,
 }
 
 /// A model for creating a new user token in the database.
@@ -122,8 +136,11 @@ pub struct NewUserToken {
     /// The user's ID.
     pub user_id: i32,
 
-    /// The token's value.
-    pub value: String,
+    /// The SHA-256 hash of the token's value.
+    pub hash: String,
+
+    /// The first few characters of the token's value.
+    pub prefix: String,
 
     /// The token's expiration date.
     pub expires: NaiveDateTime,
@@ -0,0 +1,109 @@
+use chrono::NaiveDateTime;
+use diesel::pg::Pg;
+
+use crate::{version_files, PackageVersion};
+
+/// A file belonging to a package version.
+/// Every version has exactly one primary file, and can have any number of extra files
+/// (like sources, a server-only variant, or a resource pack).
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Identifiable,
+    Queryable,
+    Selectable,
+    Associations,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = version_files)]
+#[diesel(belongs_to(PackageVersion, foreign_key = version))]
+#[diesel(check_for_backend(Pg))]
+pub struct VersionFile {
+    /// The file's ID.
+    pub id: i32,
+
+    /// The package version ID.
+    pub version: i32,
+
+    /// The file ID in the bucket.
+    #[serde(skip)]
+    pub file_id: String,
+
+    /// The file's name.
+    /// This is missing for files uploaded before it was recorded.
+    pub file_name: Option<String>,
+
+    /// The size of the file, in bytes.
+    /// This is missing for files uploaded before it was recorded.
+    pub size: Option<i64>,
+
+    /// The SHA-1 hash of the file.
+    pub sha1: String,
+
+    /// The SHA-256 hash of the file.
+    /// This is missing for files uploaded before it was recorded.
+    pub sha256: Option<String>,
+
+    /// The SHA-512 hash of the file.
+    /// This is missing for files uploaded before it was recorded.
+    pub sha512: Option<String>,
+
+    /// Whether this is the version's primary file.
+    pub is_primary: bool,
+
+    /// The date this file was uploaded.
+    pub created_at: NaiveDateTime,
+}
+
+/// A model for adding a file to a package version.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Insertable,
+    Associations,
+    ToSchema,
+    ToResponse,
+)]
+#[diesel(table_name = version_files)]
+#[diesel(belongs_to(PackageVersion, foreign_key = version))]
+#[diesel(check_for_backend(Pg))]
+pub struct NewVersionFile {
+    /// The package version ID.
+    pub version: i32,
+
+    /// The file ID in the bucket.
+    pub file_id: String,
+
+    /// The file's name.
+    pub file_name: Option<String>,
+
+    /// The size of the file, in bytes.
+    pub size: Option<i64>,
+
+    /// The SHA-1 hash of the file.
+    pub sha1: String,
+
+    /// The SHA-256 hash of the file.
+    pub sha256: Option<String>,
+
+    /// The SHA-512 hash of the file.
+    pub sha512: Option<String>,
+
+    /// Whether this is the version's primary file.
+    pub is_primary: bool,
+}
@@ -1,11 +1,55 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "author_role"))]
+    pub struct AuthorRole;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "channel"))]
+    pub struct Channel;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "org_role"))]
+    pub struct OrgRole;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "token_scope"))]
+    pub struct TokenScope;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "visibility"))]
     pub struct Visibility;
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::TokenScope;
+
+    api_tokens (id) {
+        id -> Int4,
+        user_id -> Int4,
+        name -> Text,
+        hash -> Text,
+        scopes -> Array<TokenScope>,
+        package -> Nullable<Int4>,
+        expires -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        prefix -> Text,
+        last_used -> Nullable<Timestamp>,
+        last_ip -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    daily_downloads (version, day) {
+        package -> Int4,
+        version -> Int4,
+        day -> Date,
+        downloads -> Int4,
+    }
+}
+
 diesel::table! {
     gallery_images (id) {
         id -> Int4,
@@ -20,9 +64,35 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::OrgRole;
+
+    organization_members (organization, user_id) {
+        organization -> Int4,
+        user_id -> Int4,
+        role -> OrgRole,
+    }
+}
+
+diesel::table! {
+    organizations (id) {
+        id -> Int4,
+        name -> Text,
+        slug -> Text,
+        description -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AuthorRole;
+
     package_authors (package, user_id) {
         package -> Int4,
         user_id -> Int4,
+        role -> AuthorRole,
     }
 }
 
@@ -41,6 +111,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Channel;
+
     package_versions (id) {
         id -> Int4,
         package -> Int4,
@@ -53,6 +126,13 @@ diesel::table! {
         downloads -> Int4,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        sha1 -> Text,
+        sha256 -> Nullable<Text>,
+        sha512 -> Nullable<Text>,
+        file_size -> Nullable<Int8>,
+        channel -> Channel,
+        yanked -> Bool,
+        deprecation -> Nullable<Text>,
     }
 }
 
@@ -75,6 +155,20 @@ diesel::table! {
         license -> Nullable<Text>,
         visibility -> Visibility,
         tags -> Array<Nullable<Text>>,
+        deprecation -> Nullable<Text>,
+        replacement -> Nullable<Int4>,
+        organization -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    user_identities (id) {
+        id -> Int4,
+        user_id -> Int4,
+        provider -> Text,
+        provider_id -> Text,
+        username -> Text,
+        created_at -> Timestamp,
     }
 }
 
@@ -82,8 +176,12 @@ diesel::table! {
     user_tokens (id) {
         id -> Int4,
         user_id -> Int4,
-        value -> Text,
+        hash -> Text,
         expires -> Timestamp,
+        prefix -> Text,
+        created_at -> Timestamp,
+        last_used -> Nullable<Timestamp>,
+        last_ip -> Nullable<Text>,
     }
 }
 
@@ -96,22 +194,65 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    version_files (id) {
+        id -> Int4,
+        version -> Int4,
+        file_id -> Text,
+        file_name -> Nullable<Text>,
+        size -> Nullable<Int8>,
+        sha1 -> Text,
+        sha256 -> Nullable<Text>,
+        sha512 -> Nullable<Text>,
+        is_primary -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::joinable!(api_tokens -> packages (package));
+diesel::joinable!(api_tokens -> users (user_id));
+diesel::joinable!(daily_downloads -> package_versions (version));
+diesel::table! {
+    version_requirements (id) {
+        id -> Int4,
+        version -> Int4,
+        package -> Int4,
+        kind -> Int4,
+        requirement -> Nullable<Text>,
+    }
+}
+
+diesel::joinable!(daily_downloads -> packages (package));
 diesel::joinable!(gallery_images -> packages (package));
+diesel::joinable!(organization_members -> organizations (organization));
+diesel::joinable!(organization_members -> users (user_id));
 diesel::joinable!(package_authors -> packages (package));
 diesel::joinable!(package_authors -> users (user_id));
 diesel::joinable!(package_relations -> package_version_refs (dependency));
 diesel::joinable!(package_relations -> package_versions (package));
 diesel::joinable!(package_version_refs -> package_versions (value));
 diesel::joinable!(package_versions -> packages (package));
+diesel::joinable!(packages -> organizations (organization));
+diesel::joinable!(user_identities -> users (user_id));
 diesel::joinable!(user_tokens -> users (user_id));
+diesel::joinable!(version_files -> package_versions (version));
+diesel::joinable!(version_requirements -> package_versions (version));
+diesel::joinable!(version_requirements -> packages (package));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_tokens,
+    daily_downloads,
     gallery_images,
+    organization_members,
+    organizations,
     package_authors,
     package_relations,
     package_version_refs,
     package_versions,
     packages,
+    user_identities,
     user_tokens,
     users,
+    version_files,
+    version_requirements,
 );
@@ -0,0 +1,69 @@
+use app_core::Result;
+use chrono::{TimeDelta, Utc};
+use diesel::{
+    update, BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+use random_string::{charsets::ALPHANUMERIC, generate};
+
+use crate::{api_tokens, hash_token, ApiToken, DbConn, TOKEN_LENGTH, TOKEN_TOUCH_INTERVAL};
+
+/// The prefix of every personal API token's value.
+/// This tells them apart from login tokens (and makes them easy to spot in leaked logs).
+pub const API_TOKEN_PREFIX: &str = "mhp_";
+
+/// Generate a new personal API token value.
+pub fn generate_api_token_value() -> String {
+    format!(
+        "{}{}",
+        API_TOKEN_PREFIX,
+        generate(TOKEN_LENGTH, ALPHANUMERIC)
+    )
+}
+
+/// Check if a token value is a personal API token.
+pub fn is_api_token(token: impl AsRef<str>) -> bool {
+    token.as_ref().starts_with(API_TOKEN_PREFIX)
+}
+
+/// Get a personal API token by its value.
+/// Expired tokens are never returned.
+pub async fn get_api_token(token: impl AsRef<str>, conn: &mut DbConn) -> Result<Option<ApiToken>> {
+    let token = api_tokens::table
+        .filter(api_tokens::hash.eq(hash_token(token)))
+        .select(ApiToken::as_select())
+        .first(conn)
+        .await
+        .optional()?;
+
+    Ok(token.filter(|it| !it.is_expired()))
+}
+
+/// Get all of a user's personal API tokens, newest first.
+pub async fn get_user_api_tokens(user: i32, conn: &mut DbConn) -> Result<Vec<ApiToken>> {
+    Ok(api_tokens::table
+        .filter(api_tokens::user_id.eq(user))
+        .order(api_tokens::created_at.desc())
+        .select(ApiToken::as_select())
+        .load(conn)
+        .await?)
+}
+
+/// Record that a personal API token was just used.
+/// This only writes to the database every [`TOKEN_TOUCH_INTERVAL`] seconds per token.
+pub async fn touch_api_token(id: i32, ip: Option<String>, conn: &mut DbConn) -> Result<()> {
+    let now = Utc::now().naive_utc();
+
+    update(api_tokens::table)
+        .filter(api_tokens::id.eq(id))
+        .filter(
+            api_tokens::last_used
+                .is_null()
+                .or(api_tokens::last_used.lt(now - TimeDelta::seconds(TOKEN_TOUCH_INTERVAL))),
+        )
+        .set((api_tokens::last_used.eq(now), api_tokens::last_ip.eq(ip)))
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
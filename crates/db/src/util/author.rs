@@ -0,0 +1,74 @@
+use app_core::Result;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+use crate::{
+    get_org_role, package_authors, users, AuthorData, AuthorRole, DbConn, PackageAccess,
+    PackagePermission, User,
+};
+
+/// Get a package's authors with their roles, owners first.
+pub async fn get_package_authors(pkg: i32, conn: &mut DbConn) -> Result<Vec<AuthorData>> {
+    Ok(package_authors::table
+        .filter(package_authors::package.eq(pkg))
+        .inner_join(users::table)
+        .order((package_authors::role.desc(), users::username.asc()))
+        .select((User::as_select(), package_authors::role))
+        .load::<(User, AuthorRole)>(conn)
+        .await?
+        .into_iter()
+        .map(|(user, role)| AuthorData { user, role })
+        .collect())
+}
+
+/// Get a user's role in a package, if they're one of its authors.
+pub async fn get_author_role(pkg: i32, user: i32, conn: &mut DbConn) -> Result<Option<AuthorRole>> {
+    Ok(package_authors::table
+        .find((pkg, user))
+        .select(package_authors::role)
+        .first(conn)
+        .await
+        .optional()?)
+}
+
+/// Get what a user can do to a package.
+/// Admins can do anything, so their roles aren't looked up.
+pub async fn get_package_access(
+    user: &User,
+    pkg: i32,
+    org: Option<i32>,
+    conn: &mut DbConn,
+) -> Result<PackageAccess> {
+    if user.admin {
+        return Ok(PackageAccess {
+            admin: true,
+            ..Default::default()
+        });
+    }
+
+    let author = get_author_role(pkg, user.id, conn).await?;
+
+    let org = match org {
+        Some(org) => get_org_role(org, user.id, conn).await?,
+        None => None,
+    };
+
+    Ok(PackageAccess {
+        admin: false,
+        author,
+        org,
+    })
+}
+
+/// Check if a user can do something to a package. This is true if their author
+/// role allows it, if their role in the organization that owns it allows it,
+/// or if they're an admin.
+pub async fn has_package_permission(
+    user: &User,
+    pkg: i32,
+    org: Option<i32>,
+    perm: PackagePermission,
+    conn: &mut DbConn,
+) -> Result<bool> {
+    Ok(get_package_access(user, pkg, org, conn).await?.allows(perm))
+}
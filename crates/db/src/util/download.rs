@@ -0,0 +1,65 @@
+use crate::{daily_downloads, package_versions, packages, DailyDownloads, DbConn, Result};
+use app_core::AppError;
+use chrono::{NaiveDate, Utc};
+use diesel::{insert_into, update, ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+
+/// Count a download of a package version, both in the package and version
+/// totals and in today's download statistics. Either all of the counts go up or none do.
+pub async fn record_download(pkg: i32, ver: i32, conn: &mut DbConn) -> Result<()> {
+    conn.transaction::<_, AppError, _>(|conn| {
+        async move {
+            update(packages::table)
+                .filter(packages::id.eq(pkg))
+                .set((
+                    packages::downloads.eq(packages::downloads + 1),
+                    packages::updated_at.eq(packages::updated_at),
+                ))
+                .execute(conn)
+                .await?;
+
+            update(package_versions::table)
+                .filter(package_versions::id.eq(ver))
+                .set((
+                    package_versions::downloads.eq(package_versions::downloads + 1),
+                    package_versions::updated_at.eq(package_versions::updated_at),
+                ))
+                .execute(conn)
+                .await?;
+
+            insert_into(daily_downloads::table)
+                .values(&DailyDownloads {
+                    package: pkg,
+                    version: ver,
+                    day: Utc::now().date_naive(),
+                    downloads: 1,
+                })
+                .on_conflict((daily_downloads::version, daily_downloads::day))
+                .do_update()
+                .set(daily_downloads::downloads.eq(daily_downloads::downloads + 1))
+                .execute(conn)
+                .await?;
+
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await
+}
+
+/// Get a package's daily download counts between two days (inclusive).
+pub async fn get_daily_downloads(
+    pkg: i32,
+    from: NaiveDate,
+    to: NaiveDate,
+    conn: &mut DbConn,
+) -> Result<Vec<DailyDownloads>> {
+    Ok(daily_downloads::table
+        .filter(daily_downloads::package.eq(pkg))
+        .filter(daily_downloads::day.ge(from))
+        .filter(daily_downloads::day.le(to))
+        .order(daily_downloads::day.asc())
+        .select(DailyDownloads::as_select())
+        .load(conn)
+        .await?)
+}
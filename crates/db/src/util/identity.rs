@@ -0,0 +1,30 @@
+use app_core::Result;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+use crate::{user_identities, DbConn, UserIdentity};
+
+/// Get the identity for an account on a login provider, if it's linked to a user.
+pub async fn get_identity(
+    provider: impl AsRef<str>,
+    provider_id: impl AsRef<str>,
+    conn: &mut DbConn,
+) -> Result<Option<UserIdentity>> {
+    Ok(user_identities::table
+        .filter(user_identities::provider.eq(provider.as_ref()))
+        .filter(user_identities::provider_id.eq(provider_id.as_ref()))
+        .select(UserIdentity::as_select())
+        .first(conn)
+        .await
+        .optional()?)
+}
+
+/// Get all of a user's linked identities, oldest first.
+pub async fn get_user_identities(user: i32, conn: &mut DbConn) -> Result<Vec<UserIdentity>> {
+    Ok(user_identities::table
+        .filter(user_identities::user_id.eq(user))
+        .order(user_identities::id.asc())
+        .select(UserIdentity::as_select())
+        .load(conn)
+        .await?)
+}
@@ -1,13 +1,27 @@
+mod api_token;
+mod author;
+mod download;
 mod gallery;
+mod identity;
+mod org;
 mod pkg;
+mod relation;
 mod sync;
 mod token;
 mod user;
 mod ver;
+mod ver_file;
 
+pub use api_token::*;
+pub use author::*;
+pub use download::*;
 pub use gallery::*;
+pub use identity::*;
+pub use org::*;
 pub use pkg::*;
+pub use relation::*;
 pub use sync::*;
 pub use token::*;
 pub use user::*;
 pub use ver::*;
+pub use ver_file::*;
@@ -0,0 +1,96 @@
+use app_core::Result;
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, PgTextExpressionMethods, QueryDsl,
+    SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+
+use crate::{
+    organization_members, organizations, users, DbConn, OrgMember, OrgRole, Organization,
+    OrganizationData, OrganizationMember, User,
+};
+
+/// Get an organization by its ID or slug.
+pub async fn get_org(id: impl AsRef<str>, conn: &mut DbConn) -> Result<Organization> {
+    let id = id.as_ref();
+
+    if let Ok(id) = id.parse::<i32>() {
+        let org = organizations::table
+            .find(id)
+            .select(Organization::as_select())
+            .first(conn)
+            .await
+            .optional()?;
+
+        if let Some(org) = org {
+            return Ok(org);
+        }
+    }
+
+    Ok(organizations::table
+        .filter(organizations::slug.eq(id))
+        .select(Organization::as_select())
+        .first(conn)
+        .await?)
+}
+
+/// Get an organization's members, owners first.
+pub async fn get_org_members(org: i32, conn: &mut DbConn) -> Result<Vec<OrgMember>> {
+    Ok(organization_members::table
+        .filter(organization_members::organization.eq(org))
+        .inner_join(users::table)
+        .order((organization_members::role.desc(), users::username.asc()))
+        .select((User::as_select(), organization_members::role))
+        .load::<(User, OrgRole)>(conn)
+        .await?
+        .into_iter()
+        .map(|(user, role)| OrgMember { user, role })
+        .collect())
+}
+
+/// Get an organization by its ID or slug, along with its members.
+pub async fn get_full_org(id: impl AsRef<str>, conn: &mut DbConn) -> Result<OrganizationData> {
+    let org = get_org(id, conn).await?;
+    let members = get_org_members(org.id, conn).await?;
+
+    Ok(OrganizationData { org, members })
+}
+
+/// Get a user's role in an organization, if they're in it.
+pub async fn get_org_role(org: i32, user: i32, conn: &mut DbConn) -> Result<Option<OrgRole>> {
+    Ok(organization_members::table
+        .find((org, user))
+        .select(organization_members::role)
+        .first(conn)
+        .await
+        .optional()?)
+}
+
+/// Get every organization a user is in, along with their membership.
+pub async fn get_user_orgs(
+    user: i32,
+    conn: &mut DbConn,
+) -> Result<Vec<(OrganizationMember, Organization)>> {
+    Ok(organization_members::table
+        .filter(organization_members::user_id.eq(user))
+        .inner_join(organizations::table)
+        .order(organizations::name.asc())
+        .select((OrganizationMember::as_select(), Organization::as_select()))
+        .load(conn)
+        .await?)
+}
+
+/// Search for organizations by name or slug.
+pub async fn search_orgs(name: impl AsRef<str>, conn: &mut DbConn) -> Result<Vec<Organization>> {
+    let name = name.as_ref();
+
+    Ok(organizations::table
+        .filter(
+            organizations::name
+                .ilike(format!("%{}%", name))
+                .or(organizations::slug.ilike(format!("%{}%", name))),
+        )
+        .select(Organization::as_select())
+        .load(conn)
+        .await?)
+}
@@ -0,0 +1,123 @@
+use app_core::Result;
+use diesel::{insert_into, ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+use crate::{
+    schema::{
+        package_relations, package_version_refs, package_versions, packages, version_requirements,
+    },
+    DbConn, NewVersionRequirement, Package, PackageRelation, PackageVersion, PackageVersionRef,
+    RelationKind, VersionRelation, VersionRequirement,
+};
+
+/// Store relations from a package version to other package versions.
+pub async fn add_relations(
+    ver: i32,
+    relations: Vec<(i32, RelationKind)>,
+    conn: &mut DbConn,
+) -> Result<()> {
+    for (dependency, kind) in relations {
+        insert_into(package_version_refs::table)
+            .values(&PackageVersionRef { value: dependency })
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+
+        insert_into(package_relations::table)
+            .values(&PackageRelation {
+                package: ver,
+                dependency,
+                kind,
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Store the requirements a package version declared on other packages.
+/// Each is a package ID, the relation kind, and the versions it matches.
+pub async fn add_requirements(
+    ver: i32,
+    requirements: Vec<(i32, RelationKind, Option<String>)>,
+    conn: &mut DbConn,
+) -> Result<()> {
+    let requirements = requirements
+        .into_iter()
+        .map(|(package, kind, requirement)| NewVersionRequirement {
+            version: ver,
+            package,
+            kind,
+            requirement,
+        })
+        .collect::<Vec<_>>();
+
+    insert_into(version_requirements::table)
+        .values(&requirements)
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Get the requirements that package versions declared on other packages.
+pub async fn get_requirements(vers: &[i32], conn: &mut DbConn) -> Result<Vec<VersionRequirement>> {
+    Ok(version_requirements::table
+        .filter(version_requirements::version.eq_any(vers))
+        .select(VersionRequirement::as_select())
+        .load(conn)
+        .await?)
+}
+
+/// Get the versions that a package version depends on (or is incompatible with).
+pub async fn get_dependencies(ver: i32, conn: &mut DbConn) -> Result<Vec<VersionRelation>> {
+    let relations = package_relations::table
+        .filter(package_relations::package.eq(ver))
+        .select(PackageRelation::as_select())
+        .load(conn)
+        .await?;
+
+    resolve_relations(relations.into_iter().map(|v| (v.dependency, v.kind)), conn).await
+}
+
+/// Get the versions that depend on (or are incompatible with) a package version.
+pub async fn get_dependents(ver: i32, conn: &mut DbConn) -> Result<Vec<VersionRelation>> {
+    let relations = package_relations::table
+        .filter(package_relations::dependency.eq(ver))
+        .select(PackageRelation::as_select())
+        .load(conn)
+        .await?;
+
+    resolve_relations(relations.into_iter().map(|v| (v.package, v.kind)), conn).await
+}
+
+async fn resolve_relations(
+    relations: impl IntoIterator<Item = (i32, RelationKind)>,
+    conn: &mut DbConn,
+) -> Result<Vec<VersionRelation>> {
+    let relations = relations.into_iter().collect::<Vec<_>>();
+
+    let found = package_versions::table
+        .inner_join(packages::table)
+        .filter(package_versions::id.eq_any(relations.iter().map(|v| v.0)))
+        .select((PackageVersion::as_select(), Package::as_select()))
+        .load::<(PackageVersion, Package)>(conn)
+        .await?;
+
+    Ok(relations
+        .into_iter()
+        .filter_map(|(id, kind)| {
+            found
+                .iter()
+                .find(|(ver, _)| ver.id == id)
+                .map(|(version, package)| VersionRelation {
+                    kind,
+                    package: package.clone(),
+                    version: version.clone(),
+                })
+        })
+        .collect())
+}
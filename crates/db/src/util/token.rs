@@ -1,10 +1,14 @@
 use app_core::Result;
-use chrono::{DateTime, Utc};
-use diesel::{insert_into, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::{
+    delete, insert_into, update, BoolExpressionMethods, ExpressionMethods, OptionalExtension,
+    PgSortExpressionMethods, QueryDsl, SelectableHelper,
+};
 use diesel_async::RunQueryDsl;
 use random_string::{charsets::ALPHANUMERIC, generate};
+use sha2::{Digest, Sha256};
 
-use crate::{user_tokens, users, DbConn, DbPool, NewUserToken, User, UserToken};
+use crate::{api_tokens, user_tokens, users, DbConn, DbPool, NewUserToken, User, UserToken};
 
 /// The time until a token expires in milliseconds.
 /// Calculation: 1 * SECS_PER_WEEK * MILLIS_PER_SEC
@@ -12,33 +16,67 @@ use crate::{user_tokens, users, DbConn, DbPool, NewUserToken, User, UserToken};
 pub const TOKEN_EXPIRE_TIME: i64 = 1 * 604800 * 1000;
 pub const TOKEN_LENGTH: usize = 64;
 
-pub fn generate_token(user_id: i32) -> NewUserToken {
-    NewUserToken {
+/// How many characters of a token's value are stored to tell it apart from others.
+pub const TOKEN_PREFIX_LENGTH: usize = 12;
+
+/// How often a token's last used time (and IP) is updated, in seconds.
+/// This keeps every single request from writing to the database.
+pub const TOKEN_TOUCH_INTERVAL: i64 = 60;
+
+/// Hash a token's value for storing or looking it up.
+pub fn hash_token(token: impl AsRef<str>) -> String {
+    format!("{:x}", Sha256::digest(token.as_ref().as_bytes()))
+}
+
+/// Get the prefix of a token's value that gets stored with its hash.
+pub fn token_prefix(token: impl AsRef<str>) -> String {
+    token.as_ref().chars().take(TOKEN_PREFIX_LENGTH).collect()
+}
+
+/// Generate a new login token, returning its value and the model to insert.
+pub fn generate_token(user_id: i32) -> (String, NewUserToken) {
+    let value = generate(TOKEN_LENGTH, ALPHANUMERIC);
+
+    let token = NewUserToken {
         user_id,
-        value: generate(TOKEN_LENGTH, ALPHANUMERIC),
+        hash: hash_token(&value),
+        prefix: token_prefix(&value),
         expires: DateTime::from_timestamp_millis(Utc::now().timestamp_millis() + TOKEN_EXPIRE_TIME)
             .unwrap()
             .naive_utc(),
-    }
+    };
+
+    (value, token)
 }
 
-pub async fn create_token(user_id: i32, pool: &DbPool) -> Result<UserToken> {
-    Ok(insert_into(user_tokens::table)
-        .values(&generate_token(user_id))
+/// Create a new login token, returning it along with its value.
+/// The value is never stored, so this is the only chance to get it.
+pub async fn create_token(user_id: i32, pool: &DbPool) -> Result<(UserToken, String)> {
+    let (value, token) = generate_token(user_id);
+
+    let token = insert_into(user_tokens::table)
+        .values(&token)
         .returning(UserToken::as_returning())
         .get_result(&mut pool.get().await?)
-        .await?)
+        .await?;
+
+    Ok((token, value))
 }
 
-pub async fn get_user_for_token(token: impl AsRef<str>, conn: &mut DbConn) -> Result<Option<User>> {
-    let token = user_tokens::table
-        .filter(user_tokens::value.eq(token.as_ref().to_string()))
+/// Get a login token by its value.
+/// Expired tokens are never returned.
+pub async fn get_token(token: impl AsRef<str>, conn: &mut DbConn) -> Result<Option<UserToken>> {
+    Ok(user_tokens::table
+        .filter(user_tokens::hash.eq(hash_token(token)))
+        .filter(user_tokens::expires.gt(Utc::now().naive_utc()))
         .select(UserToken::as_select())
         .first(conn)
         .await
-        .optional()?;
+        .optional()?)
+}
 
-    if let Some(token) = token {
+pub async fn get_user_for_token(token: impl AsRef<str>, conn: &mut DbConn) -> Result<Option<User>> {
+    if let Some(token) = get_token(token, conn).await? {
         Ok(users::table
             .filter(users::id.eq(token.user_id))
             .select(User::as_select())
@@ -49,3 +87,54 @@ pub async fn get_user_for_token(token: impl AsRef<str>, conn: &mut DbConn) -> Re
         Ok(None)
     }
 }
+
+/// Get all of a user's active login tokens, most recently used first.
+pub async fn get_user_sessions(user: i32, conn: &mut DbConn) -> Result<Vec<UserToken>> {
+    Ok(user_tokens::table
+        .filter(user_tokens::user_id.eq(user))
+        .filter(user_tokens::expires.gt(Utc::now().naive_utc()))
+        .order((
+            user_tokens::last_used.desc().nulls_last(),
+            user_tokens::created_at.desc(),
+        ))
+        .select(UserToken::as_select())
+        .load(conn)
+        .await?)
+}
+
+/// Record that a login token was just used.
+/// This only writes to the database every [`TOKEN_TOUCH_INTERVAL`] seconds per token.
+pub async fn touch_token(id: i32, ip: Option<String>, conn: &mut DbConn) -> Result<()> {
+    let now = Utc::now().naive_utc();
+
+    update(user_tokens::table)
+        .filter(user_tokens::id.eq(id))
+        .filter(
+            user_tokens::last_used
+                .is_null()
+                .or(user_tokens::last_used.lt(now - TimeDelta::seconds(TOKEN_TOUCH_INTERVAL))),
+        )
+        .set((user_tokens::last_used.eq(now), user_tokens::last_ip.eq(ip)))
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Delete every expired login token and personal API token,
+/// returning how many were deleted.
+pub async fn delete_expired_tokens(conn: &mut DbConn) -> Result<usize> {
+    let now = Utc::now().naive_utc();
+
+    let sessions = delete(user_tokens::table)
+        .filter(user_tokens::expires.lt(now))
+        .execute(conn)
+        .await?;
+
+    let api = delete(api_tokens::table)
+        .filter(api_tokens::expires.lt(now))
+        .execute(conn)
+        .await?;
+
+    Ok(sessions + api)
+}
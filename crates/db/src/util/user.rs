@@ -1,8 +1,10 @@
 use app_core::Result;
 use diesel::{
-    ExpressionMethods, OptionalExtension, PgTextExpressionMethods, QueryDsl, SelectableHelper,
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, PgTextExpressionMethods, QueryDsl,
+    SelectableHelper, TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
+use std::collections::HashSet;
 
 use crate::{schema::users, DbConn, User};
 
@@ -20,8 +22,10 @@ pub async fn get_user(id: impl AsRef<str>, conn: &mut DbConn) -> Result<User> {
             return Ok(user);
         }
 
+        // Users who never logged in with GitHub have a GitHub ID of -1.
         if let Some(user) = users::table
             .filter(users::github_id.eq(id))
+            .filter(users::github_id.ne(-1))
             .select(User::as_select())
             .first(conn)
             .await
@@ -47,3 +51,31 @@ pub async fn search_users(name: impl AsRef<str>, conn: &mut DbConn) -> Result<Ve
         .load(conn)
         .await?)
 }
+
+/// Find a username nobody has taken yet, starting from the given one.
+/// If it's taken, a number is added to the end (`name-2`, `name-3`, ...).
+pub async fn unique_username(name: impl AsRef<str>, conn: &mut DbConn) -> Result<String> {
+    let name = name.as_ref();
+
+    // Any `%`s or `_`s in the name only make this match more usernames, which is fine.
+    let taken = users::table
+        .filter(
+            users::username
+                .eq(name)
+                .or(users::username.like(format!("{}-%", name))),
+        )
+        .select(users::username)
+        .load::<String>(conn)
+        .await?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    if !taken.contains(name) {
+        return Ok(name.into());
+    }
+
+    Ok((2..)
+        .map(|i| format!("{}-{}", name, i))
+        .find(|it| !taken.contains(it))
+        .unwrap())
+}
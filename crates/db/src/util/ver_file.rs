@@ -0,0 +1,74 @@
+use app_core::{AppError, Result};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+
+use crate::{schema::version_files, DbConn, VersionFile};
+
+/// Get all files for a package version, with the primary file first.
+pub async fn get_version_files(ver: i32, conn: &mut DbConn) -> Result<Vec<VersionFile>> {
+    Ok(version_files::table
+        .filter(version_files::version.eq(ver))
+        .order((version_files::is_primary.desc(), version_files::id.asc()))
+        .select(VersionFile::as_select())
+        .load(conn)
+        .await?)
+}
+
+/// Get a file for a package version by its ID or file name.
+/// If no file is given, this gets the version's primary file.
+pub async fn get_version_file(
+    ver: i32,
+    file: Option<impl AsRef<str>>,
+    conn: &mut DbConn,
+) -> Result<VersionFile> {
+    let Some(file) = file else {
+        return Ok(version_files::table
+            .filter(
+                version_files::version
+                    .eq(ver)
+                    .and(version_files::is_primary.eq(true)),
+            )
+            .select(VersionFile::as_select())
+            .first(conn)
+            .await?);
+    };
+
+    let file = file.as_ref();
+
+    if let Ok(id) = file.parse::<i32>() {
+        if let Some(found) = version_files::table
+            .filter(version_files::version.eq(ver).and(version_files::id.eq(id)))
+            .select(VersionFile::as_select())
+            .first(conn)
+            .await
+            .optional()?
+        {
+            return Ok(found);
+        }
+    }
+
+    version_files::table
+        .filter(
+            version_files::version
+                .eq(ver)
+                .and(version_files::file_name.eq(file)),
+        )
+        .select(VersionFile::as_select())
+        .first(conn)
+        .await
+        .optional()?
+        .ok_or(AppError::NotFound)
+}
+
+/// Check if a file in the bucket is used by any package version.
+pub async fn is_file_used(file_id: &str, conn: &mut DbConn) -> Result<bool> {
+    Ok(version_files::table
+        .filter(version_files::file_id.eq(file_id))
+        .select(version_files::id)
+        .first::<i32>(conn)
+        .await
+        .optional()?
+        .is_some())
+}
@@ -1,4 +1,7 @@
-use eyre::Result;
+use crate::{error::Result, models::ErrorBody, ModHostError};
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+use std::future::Future;
 use url::Url;
 
 pub trait ApiHelper {
@@ -11,3 +14,34 @@ pub trait ApiHelper {
         Ok(Url::parse(&format!("{}{}{}", base, slash, path.as_ref()))?)
     }
 }
+
+pub trait ResponseExt: Sized {
+    /// Turn an unsuccessful response into a [`ModHostError`].
+    fn checked(self) -> impl Future<Output = Result<Self>> + Send;
+
+    /// Check the response's status, then decode its JSON body.
+    fn decode<T: DeserializeOwned>(self) -> impl Future<Output = Result<T>> + Send;
+}
+
+impl ResponseExt for Response {
+    async fn checked(self) -> Result<Self> {
+        let status = self.status();
+
+        if status.is_success() {
+            return Ok(self);
+        }
+
+        let body = self.text().await?;
+
+        Err(ModHostError::from_status(
+            status.as_u16(),
+            ErrorBody::from_text(body),
+        ))
+    }
+
+    async fn decode<T: DeserializeOwned>(self) -> Result<T> {
+        let bytes = self.checked().await?.bytes().await?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
@@ -1,5 +1,6 @@
 mod common;
 mod pkg;
+mod resolve;
 mod users;
 mod ver;
 
@@ -8,7 +9,7 @@ pub use ver::*;
 
 pub(crate) use common::*;
 
-use eyre::Result;
+use crate::Result;
 use reqwest::{
     header::{HeaderMap, HeaderValue},
     Client, ClientBuilder,
@@ -1,9 +1,9 @@
 mod read;
 mod write;
 
-use super::{ApiClient, ApiHelper, VersionApi};
+use super::{ApiClient, ApiHelper, ResponseExt, VersionApi};
 use crate::models::{NewPackage, PackageWithData};
-use eyre::Result;
+use crate::Result;
 use reqwest::Client;
 
 #[derive(Debug, Clone)]
@@ -32,34 +32,31 @@ impl PackageApi {
 
 impl ApiClient {
     pub async fn packages(&self) -> Result<Vec<PackageWithData>> {
-        Ok(self
-            .client
+        self.client
             .get(self.url("packages")?)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 
     pub async fn search_packages(&self, query: String) -> Result<Vec<PackageWithData>> {
-        Ok(self
-            .client
+        self.client
             .get(self.url("packages/search")?)
             .query(&[("q", query)])
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 
     pub async fn create_package(&self, data: NewPackage) -> Result<PackageWithData> {
-        Ok(self
-            .client
+        self.client
             .put(self.url("packages")?)
             .json(&data)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 }
@@ -1,68 +1,75 @@
-use super::{ApiHelper, PackageApi};
-use crate::models::{PackageVersion, PackageWithData, User};
-use eyre::Result;
+use super::{ApiHelper, PackageApi, ResponseExt};
+use crate::models::{
+    PackageAuthor, PackageVersion, PackageWithData, VersionChannel, VersionFilters,
+};
+use crate::Result;
 use tokio::runtime::Handle;
 
 impl PackageApi {
     pub async fn get(&self) -> Result<PackageWithData> {
-        Ok(self
-            .client
+        self.client
             .get(self.url(format!("packages/{}", self.package))?)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 
     pub fn get_sync(&self, rt: &Handle) -> Result<PackageWithData> {
-        Ok(rt.block_on(
-            rt.block_on(
-                self.client
-                    .get(self.url(format!("packages/{}", self.package))?)
-                    .send(),
-            )?
-            .json(),
-        )?)
+        rt.block_on(self.get())
     }
 
-    pub async fn authors(&self) -> Result<Vec<User>> {
-        Ok(self
-            .client
+    pub async fn authors(&self) -> Result<Vec<PackageAuthor>> {
+        self.client
             .get(self.url(format!("packages/{}/authors", self.package))?)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 
     pub async fn versions(&self) -> Result<Vec<PackageVersion>> {
-        Ok(self
-            .client
+        self.client
             .get(self.url(format!("packages/{}/versions", self.package))?)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
+    }
+
+    pub async fn versions_matching(&self, filters: &VersionFilters) -> Result<Vec<PackageVersion>> {
+        self.client
+            .get(self.url(format!("packages/{}/versions", self.package))?)
+            .query(filters)
+            .send()
+            .await?
+            .decode()
+            .await
     }
 
     pub async fn latest_version(&self) -> Result<PackageVersion> {
-        Ok(self
-            .client
+        self.client
             .get(self.url(format!("packages/{}/versions/latest", self.package))?)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
+    }
+
+    pub async fn latest_version_on(&self, channel: VersionChannel) -> Result<PackageVersion> {
+        self.client
+            .get(self.url(format!(
+                "packages/{}/versions/latest?channel={}",
+                self.package,
+                channel.as_str()
+            ))?)
+            .send()
+            .await?
+            .decode()
+            .await
     }
 
     pub fn latest_version_sync(&self, rt: &Handle) -> Result<PackageVersion> {
-        Ok(rt.block_on(
-            rt.block_on(
-                self.client
-                    .get(self.url(format!("packages/{}/versions/latest", self.package))?)
-                    .send(),
-            )?
-            .json(),
-        )?)
+        rt.block_on(self.latest_version())
     }
 }
@@ -1,81 +1,135 @@
-use super::{ApiHelper, PackageApi};
-use crate::models::{NewPackageVersion, PackageUpdate, PackageVersion, PackageWithData, User};
-use eyre::Result;
-use reqwest::multipart::Form;
-use std::io::Write;
-use tempfile::NamedTempFile;
+use super::{ApiHelper, PackageApi, ResponseExt};
+use crate::models::{
+    AuthorRole, NewPackageVersion, PackageAuthor, PackageUpdate, PackageWithData, UploadedVersion,
+    User,
+};
+use crate::Result;
+use reqwest::multipart::{Form, Part};
 
 impl PackageApi {
     pub async fn delete(&self) -> Result<()> {
         self.client
             .delete(self.url(format!("packages/{}", self.package))?)
             .send()
+            .await?
+            .checked()
             .await?;
 
         Ok(())
     }
 
     pub async fn update(&self, data: PackageUpdate) -> Result<PackageWithData> {
-        Ok(self
-            .client
+        self.client
             .patch(self.url(format!("packages/{}", self.package))?)
             .json(&data)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 
     pub async fn add_author(&self, user: User) -> Result<PackageWithData> {
-        Ok(self
-            .client
+        self.client
             .put(self.url(format!("packages/{}/authors", self.package))?)
             .body(user.id.to_string())
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 
     pub async fn add_author_from_id(&self, user_id: String) -> Result<PackageWithData> {
-        Ok(self
-            .client
+        self.client
             .put(self.url(format!("packages/{}/authors", self.package))?)
             .body(user_id)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
+    }
+
+    pub async fn add_author_with_role(
+        &self,
+        user_id: String,
+        role: AuthorRole,
+    ) -> Result<PackageWithData> {
+        self.client
+            .put(self.url(format!("packages/{}/authors", self.package))?)
+            .query(&[("role", role)])
+            .body(user_id)
+            .send()
+            .await?
+            .decode()
+            .await
+    }
+
+    pub async fn set_author_role(
+        &self,
+        user_id: String,
+        role: AuthorRole,
+    ) -> Result<Vec<PackageAuthor>> {
+        self.client
+            .patch(self.url(format!("packages/{}/authors/{}", self.package, user_id))?)
+            .json(&serde_json::json!({ "role": role }))
+            .send()
+            .await?
+            .decode()
+            .await
     }
 
     pub async fn upload_version(
         &self,
         data: NewPackageVersion,
         file: Vec<u8>,
-    ) -> Result<PackageVersion> {
-        let mut temp_file = NamedTempFile::new()?;
+    ) -> Result<UploadedVersion> {
+        self.upload_version_with_files(data, file, Vec::new()).await
+    }
+
+    /// Upload a version with a primary file and any number of extra (named) files.
+    pub async fn upload_version_with_files(
+        &self,
+        data: NewPackageVersion,
+        file: Vec<u8>,
+        extra_files: Vec<(String, Vec<u8>)>,
+    ) -> Result<UploadedVersion> {
         let mut form = Form::new();
 
-        temp_file.write_all(file.as_slice())?;
+        if let Some(name) = data.name {
+            form = form.text("name", name);
+        }
 
-        form = form.text("name", data.name);
-        form = form.text("version_number", data.version_number);
+        if let Some(version_number) = data.version_number {
+            form = form.text("version_number", version_number);
+        }
 
         if let Some(changelog) = data.changelog {
             form = form.text("changelog", changelog);
         }
 
-        form = form.text("loaders", data.loaders.join(","));
-        form = form.text("game_versions", data.game_versions.join(","));
-        form = form.file("file", temp_file.path()).await?;
+        if let Some(loaders) = data.loaders {
+            form = form.text("loaders", loaders.join(","));
+        }
 
-        Ok(self
-            .client
+        if let Some(game_versions) = data.game_versions {
+            form = form.text("game_versions", game_versions.join(","));
+        }
+
+        if let Some(channel) = data.channel {
+            form = form.text("channel", channel.as_str());
+        }
+
+        form = form.part("file", Part::bytes(file));
+
+        for (name, file) in extra_files {
+            form = form.part("file", Part::bytes(file).file_name(name));
+        }
+
+        self.client
             .put(self.url(format!("packages/{}/versions", self.package))?)
             .multipart(form)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 }
@@ -0,0 +1,24 @@
+use super::{ApiClient, ApiHelper, ResponseExt};
+use crate::models::{ResolveRequest, ResolveResults};
+use crate::Result;
+
+impl ApiClient {
+    pub async fn resolve(
+        &self,
+        packages: Vec<String>,
+        loader: impl AsRef<str>,
+        game_version: impl AsRef<str>,
+    ) -> Result<ResolveResults> {
+        self.client
+            .post(self.url("resolve")?)
+            .json(&ResolveRequest {
+                packages,
+                loader: loader.as_ref().into(),
+                game_version: game_version.as_ref().into(),
+            })
+            .send()
+            .await?
+            .decode()
+            .await
+    }
+}
@@ -1,46 +1,152 @@
-use super::{ApiClient, ApiHelper};
-use crate::models::{PackageWithData, User};
-use eyre::Result;
+use super::{ApiClient, ApiHelper, ResponseExt};
+use crate::models::{
+    ApiToken, AuthProvider, CreatedApiToken, NewApiToken, PackageWithData, Session, User,
+    UserIdentity,
+};
+use crate::Result;
 
 impl ApiClient {
     pub async fn current_user(&self) -> Result<User> {
-        Ok(self
-            .client
+        self.client
             .get(self.url("users/me")?)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 
     pub async fn get_user(&self, user: String) -> Result<User> {
-        Ok(self
-            .client
+        self.client
             .get(self.url(format!("users/{}", user))?)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 
     pub async fn search_users(&self, query: String) -> Result<Vec<User>> {
-        Ok(self
-            .client
+        self.client
             .get(self.url("users/search")?)
             .query(&[("q", query)])
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 
     pub async fn user_packages(&self, user: String) -> Result<Vec<PackageWithData>> {
-        Ok(self
-            .client
+        self.client
             .get(self.url(format!("users/{}/packages", user))?)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
+    }
+
+    /// List the current user's personal API tokens.
+    /// This needs a login token.
+    pub async fn tokens(&self) -> Result<Vec<ApiToken>> {
+        self.client
+            .get(self.url("users/me/tokens")?)
+            .send()
+            .await?
+            .decode()
+            .await
+    }
+
+    /// Create a personal API token for the current user.
+    /// This needs a login token.
+    pub async fn create_token(&self, data: NewApiToken) -> Result<CreatedApiToken> {
+        self.client
+            .put(self.url("users/me/tokens")?)
+            .json(&data)
+            .send()
+            .await?
+            .decode()
+            .await
+    }
+
+    /// Delete one of the current user's personal API tokens.
+    /// This needs a login token.
+    pub async fn delete_token(&self, id: i32) -> Result<()> {
+        self.client
+            .delete(self.url(format!("users/me/tokens/{}", id))?)
+            .send()
+            .await?
+            .checked()
+            .await?;
+
+        Ok(())
+    }
+
+    /// List the current user's active login sessions.
+    /// This needs a login token.
+    pub async fn sessions(&self) -> Result<Vec<Session>> {
+        self.client
+            .get(self.url("users/me/sessions")?)
+            .send()
+            .await?
+            .decode()
+            .await
+    }
+
+    /// Log out one of the current user's sessions.
+    /// This needs a login token.
+    pub async fn revoke_session(&self, id: i32) -> Result<()> {
+        self.client
+            .delete(self.url(format!("users/me/sessions/{}", id))?)
+            .send()
+            .await?
+            .checked()
+            .await?;
+
+        Ok(())
+    }
+
+    /// List the login provider accounts linked to the current user.
+    /// This needs a login token.
+    pub async fn identities(&self) -> Result<Vec<UserIdentity>> {
+        self.client
+            .get(self.url("users/me/identities")?)
+            .send()
+            .await?
+            .decode()
+            .await
+    }
+
+    /// Unlink a login provider account from the current user.
+    /// This needs a login token.
+    pub async fn unlink_identity(&self, id: i32) -> Result<()> {
+        self.client
+            .delete(self.url(format!("users/me/identities/{}", id))?)
+            .send()
+            .await?
+            .checked()
+            .await?;
+
+        Ok(())
+    }
+
+    /// List the login providers configured on the server.
+    pub async fn auth_providers(&self) -> Result<Vec<AuthProvider>> {
+        self.client
+            .get(self.url("auth/providers")?)
+            .send()
+            .await?
+            .decode()
+            .await
+    }
+
+    /// Delete the token this client is using.
+    /// The client can't make authenticated requests after this.
+    pub async fn logout(&self) -> Result<()> {
+        self.client
+            .post(self.url("auth/logout")?)
+            .send()
+            .await?
+            .checked()
+            .await?;
+
+        Ok(())
     }
 }
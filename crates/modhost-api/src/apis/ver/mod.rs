@@ -1,7 +1,7 @@
 mod read;
 mod write;
 
-use super::ApiHelper;
+use super::{ApiHelper, ResponseExt};
 use reqwest::Client;
 
 #[derive(Debug, Clone)]
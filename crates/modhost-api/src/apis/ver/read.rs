@@ -1,34 +1,53 @@
-use super::{ApiHelper, VersionApi};
-use crate::models::PackageVersion;
-use eyre::Result;
+use super::{ApiHelper, ResponseExt, VersionApi};
+use crate::models::{PackageVersion, VersionFile};
+use crate::Result;
 use tokio::runtime::Handle;
 
 impl VersionApi {
     pub async fn get(&self) -> Result<PackageVersion> {
-        Ok(self
-            .client
+        self.client
             .get(self.url(format!(
                 "packages/{}/versions/{}",
                 self.package, self.version
             ))?)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 
     pub fn get_sync(&self, rt: &Handle) -> Result<PackageVersion> {
-        Ok(rt.block_on(
-            rt.block_on(
-                self.client
-                    .get(self.url(format!(
-                        "packages/{}/versions/{}",
-                        self.package, self.version
-                    ))?)
-                    .send(),
-            )?
-            .json(),
-        )?)
+        rt.block_on(self.get())
+    }
+
+    pub async fn files(&self) -> Result<Vec<VersionFile>> {
+        self.client
+            .get(self.url(format!(
+                "packages/{}/versions/{}/files",
+                self.package, self.version
+            ))?)
+            .send()
+            .await?
+            .decode()
+            .await
+    }
+
+    /// Download one of this version's files by its ID or name.
+    pub async fn download_file(&self, file: impl AsRef<str>) -> Result<Vec<u8>> {
+        Ok(self
+            .client
+            .get(self.url(format!(
+                "packages/{}/versions/{}/download",
+                self.package, self.version
+            ))?)
+            .query(&[("file", file.as_ref())])
+            .send()
+            .await?
+            .checked()
+            .await?
+            .bytes()
+            .await?
+            .to_vec())
     }
 
     pub async fn download(&self) -> Result<Vec<u8>> {
@@ -40,24 +59,14 @@ impl VersionApi {
             ))?)
             .send()
             .await?
+            .checked()
+            .await?
             .bytes()
             .await?
             .to_vec())
     }
 
     pub fn download_sync(&self, rt: &Handle) -> Result<Vec<u8>> {
-        Ok(rt
-            .block_on(
-                rt.block_on(
-                    self.client
-                        .get(self.url(format!(
-                            "packages/{}/versions/{}/download",
-                            self.package, self.version
-                        ))?)
-                        .send(),
-                )?
-                .bytes(),
-            )?
-            .to_vec())
+        rt.block_on(self.download())
     }
 }
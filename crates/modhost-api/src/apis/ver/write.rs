@@ -1,6 +1,6 @@
-use super::{ApiHelper, VersionApi};
+use super::{ApiHelper, ResponseExt, VersionApi};
 use crate::models::{PackageVersion, PackageVersionUpdate};
-use eyre::Result;
+use crate::Result;
 
 impl VersionApi {
     pub async fn delete(&self) -> Result<()> {
@@ -10,14 +10,15 @@ impl VersionApi {
                 self.package, self.version
             ))?)
             .send()
+            .await?
+            .checked()
             .await?;
 
         Ok(())
     }
 
     pub async fn update(&self, data: PackageVersionUpdate) -> Result<PackageVersion> {
-        Ok(self
-            .client
+        self.client
             .patch(self.url(format!(
                 "packages/{}/versions/{}",
                 self.package, self.version
@@ -25,7 +26,7 @@ impl VersionApi {
             .json(&data)
             .send()
             .await?
-            .json()
-            .await?)
+            .decode()
+            .await
     }
 }
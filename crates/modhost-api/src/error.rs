@@ -0,0 +1,71 @@
+use crate::models::ErrorBody;
+use thiserror::Error;
+
+/// A result that fails with a [`ModHostError`].
+pub type Result<T, E = ModHostError> = std::result::Result<T, E>;
+
+/// An error from the ModHost API client.
+#[derive(Debug, Error)]
+pub enum ModHostError {
+    /// The request couldn't be sent, or the response couldn't be read.
+    /// This usually means the server is down or unreachable.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+
+    /// The server returned an error that isn't covered by another variant.
+    #[error("The server returned an error ({status}): {}", .body.message)]
+    Status {
+        /// The HTTP status code of the response.
+        status: u16,
+
+        /// The error body the server returned.
+        body: ErrorBody,
+    },
+
+    /// The requested resource doesn't exist, or isn't visible to the current user.
+    #[error("Not found: {}", .0.message)]
+    NotFound(ErrorBody),
+
+    /// The request needs a (different) auth token.
+    #[error("Unauthorized: {}", .0.message)]
+    Unauthorized(ErrorBody),
+
+    /// The response body couldn't be decoded.
+    #[error(transparent)]
+    Decode(#[from] serde_json::Error),
+
+    /// A request URL couldn't be built.
+    #[error(transparent)]
+    Url(#[from] url::ParseError),
+
+    /// The auth token can't be used in a header.
+    #[error(transparent)]
+    InvalidToken(#[from] reqwest::header::InvalidHeaderValue),
+}
+
+impl ModHostError {
+    /// Create an error from an unsuccessful response's status and body.
+    pub fn from_status(status: u16, body: ErrorBody) -> Self {
+        match status {
+            401 | 403 => Self::Unauthorized(body),
+            404 => Self::NotFound(body),
+            _ => Self::Status { status, body },
+        }
+    }
+
+    /// The error body the server returned, if there was one.
+    pub fn body(&self) -> Option<&ErrorBody> {
+        match self {
+            Self::Status { body, .. } | Self::NotFound(body) | Self::Unauthorized(body) => {
+                Some(body)
+            }
+
+            _ => None,
+        }
+    }
+
+    /// Check if this error means the requested resource doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::NotFound(_))
+    }
+}
@@ -0,0 +1,66 @@
+use serde_json::Value;
+
+/// A stable code for the kind of error the server returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request was malformed or had an invalid field.
+    BadRequest,
+
+    /// The request needs an auth token, and none was given.
+    MissingToken,
+
+    /// The user isn't allowed to do this.
+    Unauthorized,
+
+    /// The auth token doesn't have the scope needed to do this.
+    MissingScope,
+
+    /// The requested resource doesn't exist, or isn't visible to the user.
+    NotFound,
+
+    /// The auth token doesn't belong to a known user.
+    UnknownUser,
+
+    /// An upload is over one of the server's limits.
+    PayloadTooLarge,
+
+    /// An uploaded package failed verification.
+    InvalidPackage,
+
+    /// Something went wrong on the server.
+    InternalError,
+
+    /// An error code this client doesn't know about,
+    /// or a response without an error body.
+    #[serde(other)]
+    Unknown,
+}
+
+/// The body of an error response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorBody {
+    /// What kind of error this is.
+    pub code: ErrorCode,
+
+    /// A human-readable description of the error.
+    pub message: String,
+
+    /// Extra information about the error, depending on its code.
+    #[serde(default)]
+    pub details: Option<Value>,
+}
+
+impl ErrorBody {
+    /// Parse an error body from a response's text.
+    /// If the text isn't an error body, it's used as the message.
+    pub fn from_text(text: impl AsRef<str>) -> Self {
+        let text = text.as_ref();
+
+        serde_json::from_str(text).unwrap_or_else(|_| Self {
+            code: ErrorCode::Unknown,
+            message: text.into(),
+            details: None,
+        })
+    }
+}
@@ -1,7 +1,11 @@
+mod error;
 mod pkg;
+mod resolve;
 mod user;
 mod ver;
 
+pub use error::*;
 pub use pkg::*;
+pub use resolve::*;
 pub use user::*;
 pub use ver::*;
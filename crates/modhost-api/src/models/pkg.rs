@@ -46,6 +46,63 @@ pub struct PackageUpdate {
 
     /// A link to this package's wiki.
     pub wiki: Option<String>,
+
+    /// A deprecation message for this package. An empty message removes the deprecation.
+    pub deprecation: Option<String>,
+
+    /// The ID or slug of the package replacing this one. An empty value removes it.
+    pub replacement: Option<String>,
+    /// The ID or slug of the organization to transfer this package to. An empty value removes it.
+    pub organization: Option<String>,
+}
+
+/// A struct representing a package, without any additional data.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Package {
+    /// The package's numerical ID.
+    pub id: i32,
+
+    /// This package's slug (URL ID).
+    pub slug: String,
+
+    /// The name of the package.
+    pub name: String,
+
+    /// A short description of the package.
+    pub description: String,
+
+    /// This package's README, a long-form description of the package.
+    pub readme: String,
+
+    /// A link to the source code repository of this package.
+    pub source: Option<String>,
+
+    /// A link to this package's issue tracker.
+    pub issues: Option<String>,
+
+    /// A link to this package's wiki.
+    pub wiki: Option<String>,
+
+    /// The number of downloads this package has.
+    pub downloads: i32,
+
+    /// The date/time when this package was created.
+    pub created_at: NaiveDateTime,
+
+    /// The date/time when this package was last updated.
+    pub updated_at: NaiveDateTime,
+
+    /// A deprecation message, if this package is deprecated.
+    #[serde(default)]
+    pub deprecation: Option<String>,
+
+    /// The ID of the package replacing this one, if it is deprecated.
+    #[serde(default)]
+    pub replacement: Option<i32>,
+
+    /// The ID of the organization that owns this package, if any.
+    #[serde(default)]
+    pub organization: Option<i32>,
 }
 
 /// A struct representing a package.
@@ -86,4 +143,44 @@ pub struct PackageWithData {
 
     /// The date/time when this package was last updated.
     pub updated_at: NaiveDateTime,
+
+    /// A deprecation message, if this package is deprecated.
+    #[serde(default)]
+    pub deprecation: Option<String>,
+
+    /// The ID of the package replacing this one, if it is deprecated.
+    #[serde(default)]
+    pub replacement: Option<i32>,
+
+    /// The ID of the organization that owns this package, if any.
+    #[serde(default)]
+    pub organization: Option<i32>,
+}
+
+/// An author's role in a package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorRole {
+    /// Can manage the package's gallery images.
+    GalleryEditor,
+
+    /// Can upload, edit, and delete versions.
+    Uploader,
+
+    /// Can do everything but delete or transfer the package and manage its owners.
+    Maintainer,
+
+    /// Can do everything.
+    Owner,
+}
+
+/// A struct representing a package author, with their role.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct PackageAuthor {
+    /// The user.
+    #[serde(flatten)]
+    pub user: User,
+
+    /// The author's role in the package.
+    pub role: AuthorRole,
 }
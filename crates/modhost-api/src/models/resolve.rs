@@ -0,0 +1,47 @@
+use super::{Package, PackageVersion};
+
+/// A struct representing a request to resolve a set of packages.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ResolveRequest {
+    /// The package IDs or slugs to resolve.
+    pub packages: Vec<String>,
+
+    /// The loader the packages need to work on.
+    pub loader: String,
+
+    /// The game version the packages need to work on.
+    pub game_version: String,
+}
+
+/// A struct representing a package version picked by the resolver.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ResolvedPackage {
+    /// The package.
+    pub package: Package,
+
+    /// The version of the package that was picked.
+    pub version: PackageVersion,
+}
+
+/// A struct representing a conflict between two resolved package versions.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ResolveConflict {
+    /// The version that declared the incompatibility.
+    pub version: PackageVersion,
+
+    /// The version it is incompatible with.
+    pub incompatible_with: PackageVersion,
+}
+
+/// A struct representing the result of resolving a set of packages.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ResolveResults {
+    /// The packages (including dependencies) to install.
+    pub packages: Vec<ResolvedPackage>,
+
+    /// Packages that either don't exist or have no compatible version.
+    pub unresolved: Vec<String>,
+
+    /// Incompatibilities between the resolved versions.
+    pub conflicts: Vec<ResolveConflict>,
+}
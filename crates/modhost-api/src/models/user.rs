@@ -1,3 +1,5 @@
+use chrono::NaiveDateTime;
+
 /// A struct representing a user.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct User {
@@ -10,3 +12,143 @@ pub struct User {
     /// The user's username.
     pub username: String,
 }
+
+/// Something a personal API token is allowed to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// Read packages and versions, including private ones the user can see.
+    Read,
+
+    /// Create and update packages, versions, and gallery images.
+    Publish,
+
+    /// Add and remove package authors.
+    ManageAuthors,
+
+    /// Use the user's admin permissions.
+    Admin,
+}
+
+/// A struct representing a personal API token.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ApiToken {
+    /// The token's ID.
+    pub id: i32,
+
+    /// The ID of the user this token belongs to.
+    pub user_id: i32,
+
+    /// The token's name.
+    pub name: String,
+
+    /// The first few characters of the token's value.
+    pub prefix: String,
+
+    /// What the token is allowed to do.
+    pub scopes: Vec<TokenScope>,
+
+    /// The ID of the package this token is restricted to, if any.
+    pub package: Option<i32>,
+
+    /// The token's expiration date, if it has one.
+    pub expires: Option<NaiveDateTime>,
+
+    /// When the token was created.
+    pub created_at: NaiveDateTime,
+
+    /// When the token was last used, if it has been.
+    pub last_used: Option<NaiveDateTime>,
+
+    /// The IP address the token was last used from, if it's known.
+    pub last_ip: Option<String>,
+}
+
+/// The data for creating a personal API token.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct NewApiToken {
+    /// The token's name.
+    pub name: String,
+
+    /// What the token is allowed to do.
+    pub scopes: Vec<TokenScope>,
+
+    /// The ID or slug of a package to restrict the token to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+
+    /// When the token should expire.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<NaiveDateTime>,
+}
+
+/// A newly created personal API token, including its value.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    /// The token.
+    #[serde(flatten)]
+    pub token: ApiToken,
+
+    /// The token's value. This is only ever returned once.
+    pub value: String,
+}
+
+/// A struct representing one of a user's login sessions.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Session {
+    /// The session's ID.
+    pub id: i32,
+
+    /// The ID of the user this session belongs to.
+    pub user_id: i32,
+
+    /// The first few characters of the session's token.
+    pub prefix: String,
+
+    /// When the session expires.
+    pub expires: NaiveDateTime,
+
+    /// When the session was created.
+    pub created_at: NaiveDateTime,
+
+    /// When the session was last used, if it has been.
+    pub last_used: Option<NaiveDateTime>,
+
+    /// The IP address the session was last used from, if it's known.
+    pub last_ip: Option<String>,
+
+    /// Whether this is the session the client is using.
+    pub current: bool,
+}
+
+/// A struct representing a login provider account linked to a user.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct UserIdentity {
+    /// The identity's ID.
+    pub id: i32,
+
+    /// The ID of the user this identity belongs to.
+    pub user_id: i32,
+
+    /// The login provider's ID.
+    pub provider: String,
+
+    /// The account's ID on the provider.
+    pub provider_id: String,
+
+    /// The account's username on the provider.
+    pub username: String,
+
+    /// When the identity was linked.
+    pub created_at: NaiveDateTime,
+}
+
+/// A struct representing a login provider configured on the server.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct AuthProvider {
+    /// The provider's ID.
+    pub id: String,
+
+    /// The provider's display name.
+    pub name: String,
+}
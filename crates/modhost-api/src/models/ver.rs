@@ -1,22 +1,129 @@
 use chrono::NaiveDateTime;
 
-/// A struct representing the fields required to create a new package version.
+/// The release channel of a package version.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionChannel {
+    /// A stable release.
+    #[default]
+    #[serde(alias = "Release")]
+    Release,
+
+    /// A beta (pre-release) version.
+    #[serde(alias = "Beta")]
+    Beta,
+
+    /// An alpha (early pre-release) version.
+    #[serde(alias = "Alpha")]
+    Alpha,
+}
+
+impl VersionChannel {
+    /// Get the name of this channel, as used in query parameters and form fields.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Release => "release",
+            Self::Beta => "beta",
+            Self::Alpha => "alpha",
+        }
+    }
+}
+
+/// A struct representing a file belonging to a package version.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct VersionFile {
+    /// The file's numerical ID.
+    pub id: i32,
+
+    /// The ID of the version this file belongs to.
+    pub version: i32,
+
+    /// The file's name, if it was recorded.
+    pub file_name: Option<String>,
+
+    /// The size of the file in bytes, if it was recorded.
+    pub size: Option<i64>,
+
+    /// The SHA-1 hash of the file.
+    pub sha1: String,
+
+    /// The SHA-256 hash of the file, if it was recorded.
+    pub sha256: Option<String>,
+
+    /// The SHA-512 hash of the file, if it was recorded.
+    pub sha512: Option<String>,
+
+    /// Whether this is the version's primary file.
+    pub is_primary: bool,
+
+    /// The date/time when this file was uploaded.
+    pub created_at: NaiveDateTime,
+}
+
+/// Filters for listing a package's versions.
+/// Fields left empty aren't filtered on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct VersionFilters {
+    /// Whether to include yanked versions.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub include_yanked: bool,
+
+    /// A version range, like `^1.2`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<String>,
+
+    /// A loader the versions must work on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loader: Option<String>,
+
+    /// A game version the versions must work on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game_version: Option<String>,
+
+    /// The least stable release channel to include.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<VersionChannel>,
+
+    /// The page to get (starting at 1).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<usize>,
+
+    /// How many versions to get per page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<usize>,
+
+    /// What to sort by: `version`, `published`, or `updated`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+
+    /// The sort direction: `asc` or `desc`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+}
+
+/// A struct representing the fields used to create a new package version.
+/// Any fields left empty are filled in from the package's manifest.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct NewPackageVersion {
     /// The display name of this version.
-    pub name: String,
+    pub name: Option<String>,
 
     /// This version's version number.
-    pub version_number: String,
+    pub version_number: Option<String>,
 
     /// An optional markdown-formatted changelog.
     pub changelog: Option<String>,
 
     /// A list of loaders this version works on.
-    pub loaders: Vec<String>,
+    pub loaders: Option<Vec<String>>,
 
     /// A list of game versions this version works on.
-    pub game_versions: Vec<String>,
+    pub game_versions: Option<Vec<String>>,
+
+    /// The release channel of this version.
+    pub channel: Option<VersionChannel>,
 }
 
 /// A struct representing the fields that can be updated in a package version.
@@ -36,6 +143,15 @@ pub struct PackageVersionUpdate {
 
     /// A list of game versions this version works on.
     pub game_versions: Option<Vec<String>>,
+
+    /// The release channel of this version.
+    pub channel: Option<VersionChannel>,
+
+    /// Whether this version is yanked.
+    pub yanked: Option<bool>,
+
+    /// A deprecation message for this version. An empty message removes the deprecation.
+    pub deprecation: Option<String>,
 }
 
 /// A struct representing a package version.
@@ -70,4 +186,39 @@ pub struct PackageVersion {
 
     /// The date/time when this version was last updated.
     pub updated_at: NaiveDateTime,
+
+    /// The SHA-1 hash of this version's file.
+    pub sha1: String,
+
+    /// The SHA-256 hash of this version's file, if it was recorded.
+    pub sha256: Option<String>,
+
+    /// The SHA-512 hash of this version's file, if it was recorded.
+    pub sha512: Option<String>,
+
+    /// The size of this version's file in bytes, if it was recorded.
+    pub file_size: Option<i64>,
+
+    /// The release channel of this version.
+    pub channel: VersionChannel,
+
+    /// Whether this version has been yanked.
+    #[serde(default)]
+    pub yanked: bool,
+
+    /// A deprecation message, if this version is deprecated.
+    #[serde(default)]
+    pub deprecation: Option<String>,
+}
+
+/// A newly uploaded package version.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct UploadedVersion {
+    /// The version.
+    #[serde(flatten)]
+    pub version: PackageVersion,
+
+    /// Problems the server found with the package that didn't stop it from being uploaded.
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
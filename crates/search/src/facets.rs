@@ -1,18 +1,21 @@
 use anyhow::anyhow;
 use app_core::Result;
 use chrono::NaiveDateTime;
-use db::PackageVisibility;
+use db::{PackageVisibility, VersionChannel};
+use itertools::Itertools;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
 pub enum Facet {
     GameVersions(Vec<String>),
     Loaders(Vec<String>),
     Tags(Vec<String>),
+    Channels(Vec<String>),
     Published(NaiveDateTime, NaiveDateTime),
     Updated(NaiveDateTime, NaiveDateTime),
     Downloads(i32, i32),
     Visibility(PackageVisibility),
     Author(i32),
+    Organizations(Vec<i32>),
     Manual(String),
 }
 
@@ -82,6 +85,7 @@ impl Facet {
                 Self::GameVersions(v) => format!("game_versions IN [{}]", v.join(", ")),
                 Self::Loaders(v) => format!("loaders IN [{}]", v.join(", ")),
                 Self::Tags(v) => format!("tags IN [{}]", v.join(", ")),
+                Self::Channels(v) => format!("channels IN [{}]", v.join(", ")),
                 Self::Published(start, end) => format!(
                     "(created_at >= {}) AND (created_at <= {})",
                     start.and_utc().timestamp(),
@@ -95,6 +99,7 @@ impl Facet {
                 Self::Downloads(start, end) =>
                     format!("(downloads >= {}) AND (downloads <= {})", start, end),
                 Self::Author(v) => format!("author_ids IN [{}]", v),
+                Self::Organizations(v) => format!("organization IN [{}]", v.iter().join(", ")),
                 Self::Manual(s) => s,
             }
         )
@@ -102,11 +107,17 @@ impl Facet {
 
     pub fn parse(it: (String, Vec<String>)) -> Result<Facet> {
         match it.0.as_str() {
-            // 'visibility', and 'author', and 'manual' can only be set by the system for security reasons
+            // 'visibility', 'author', 'organizations', and 'manual' can only be set by the system for security reasons
             "game_versions" => Ok(Facet::GameVersions(it.1)),
             "loaders" => Ok(Facet::Loaders(it.1)),
             "tags" => Ok(Facet::Tags(it.1)),
 
+            "channels" => Ok(Facet::Channels(
+                it.1.iter()
+                    .map(|v| v.parse::<VersionChannel>().map(|v| v.as_str().to_string()))
+                    .collect::<Result<Vec<_>>>()?,
+            )),
+
             "published" => {
                 if it.1.len() == 2 {
                     Ok(Self::Published(it.1[0].parse()?, it.1[1].parse()?))
@@ -1,5 +1,5 @@
 use chrono::NaiveDateTime;
-use db::{Package, PackageData, PackageVersion, PackageVisibility, User};
+use db::{Package, PackageData, PackageVersion, PackageVisibility, User, VersionChannel};
 use itertools::Itertools;
 
 /// A package for search indexing.
@@ -66,6 +66,21 @@ pub struct MeiliPackage {
 
     /// A list of tags for this package.
     pub tags: Vec<String>,
+
+    /// A list of release channels this package has versions on.
+    pub channels: Vec<VersionChannel>,
+
+    /// A deprecation message, if this package is deprecated.
+    #[serde(default)]
+    pub deprecation: Option<String>,
+
+    /// The ID of the package that replaces this one, if it is deprecated.
+    #[serde(default)]
+    pub replacement: Option<i32>,
+
+    /// The ID of the organization that owns this package, if one does.
+    #[serde(default)]
+    pub organization: Option<i32>,
 }
 
 #[derive(
@@ -95,10 +110,14 @@ impl MeiliPackage {
             wiki: pkg.wiki,
             visibility: pkg.visibility,
             license: pkg.license,
+            deprecation: pkg.deprecation,
+            replacement: pkg.replacement,
+            organization: pkg.organization,
             version_ids: versions.iter().map(|v| v.id).collect_vec(),
             author_ids: authors.iter().map(|v| v.id).collect_vec(),
             loaders: versions
                 .iter()
+                .filter(|v| !v.yanked)
                 .flat_map(|v| v.loaders.clone())
                 .filter_map(|v| v)
                 .sorted()
@@ -106,12 +125,20 @@ impl MeiliPackage {
                 .collect_vec(),
             game_versions: versions
                 .iter()
+                .filter(|v| !v.yanked)
                 .flat_map(|v| v.game_versions.clone())
                 .filter_map(|v| v)
                 .sorted()
                 .dedup()
                 .collect_vec(),
             tags: pkg.tags.into_iter().filter_map(|v| v).collect_vec(),
+            channels: versions
+                .iter()
+                .filter(|v| !v.yanked)
+                .map(|v| v.channel)
+                .sorted()
+                .dedup()
+                .collect_vec(),
             authors,
             versions,
         }
@@ -134,6 +161,9 @@ impl MeiliPackage {
             license: self.license,
             authors: self.authors,
             tags: self.tags,
+            deprecation: self.deprecation,
+            replacement: self.replacement,
+            organization: self.organization,
         }
     }
 }
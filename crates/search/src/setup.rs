@@ -25,6 +25,8 @@ impl MeilisearchService {
                 "created_at",
                 "updated_at",
                 "tags",
+                "channels",
+                "organization",
             ])
             .await?;
 
@@ -49,6 +51,8 @@ impl MeilisearchService {
                 "created_at",
                 "updated_at",
                 "tags",
+                "channels",
+                "organization",
             ])
             .await?;
 
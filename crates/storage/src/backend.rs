@@ -0,0 +1,45 @@
+use app_core::Result;
+use bytes::Bytes;
+use futures::Stream;
+use std::{path::Path, pin::Pin};
+
+/// A stream of a file's contents.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
+/// A place where files (packages, gallery images, etc.) can be stored.
+#[async_trait::async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Store a file under a key, replacing it if it already exists.
+    async fn put(&self, key: &str, data: &[u8]) -> Result<()>;
+
+    /// Store a file from the local filesystem under a key, without loading it all into memory.
+    async fn put_file(&self, key: &str, path: &Path) -> Result<()>;
+
+    /// Get the contents of a file.
+    async fn get(&self, key: &str) -> Result<Vec<u8>>;
+
+    /// Delete a file.
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// Get the size of a file, in bytes.
+    async fn size(&self, key: &str) -> Result<u64>;
+
+    /// Stream the contents of a file without loading it all into memory.
+    /// If a range is given, only the bytes from `start` to `end` (inclusive) are streamed.
+    async fn stream(&self, key: &str, range: Option<(u64, u64)>) -> Result<ByteStream>;
+
+    /// Get a short-lived URL that a file can be downloaded from directly.
+    /// Returns `None` if this backend can't serve files on its own.
+    async fn presign(&self, key: &str, expiry_secs: u32, file_name: &str)
+        -> Result<Option<String>>;
+}
+
+/// Build a `Content-Disposition` header value for downloading a file with a name.
+pub fn content_disposition(file_name: &str) -> String {
+    let file_name = file_name
+        .chars()
+        .filter(|v| v.is_ascii_graphic() && *v != '"' && *v != '\\' || *v == ' ')
+        .collect::<String>();
+
+    format!("attachment; filename=\"{}\"", file_name)
+}
@@ -0,0 +1,99 @@
+use crate::{content_disposition, ByteStream, StorageBackend};
+use anyhow::anyhow;
+use app_core::Result;
+use futures::TryStreamExt;
+use s3::{
+    command::Command,
+    request::{tokio_backend::HyperRequest, Request},
+    Bucket,
+};
+use std::{collections::HashMap, path::Path};
+use tokio::fs::File;
+
+/// A storage backend using an S3 bucket.
+#[derive(Debug, Clone)]
+pub struct S3Storage {
+    bucket: Box<Bucket>,
+}
+
+impl S3Storage {
+    /// Create a new S3 storage backend for a bucket.
+    pub fn new(bucket: Box<Bucket>) -> Self {
+        Self { bucket }
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for S3Storage {
+    async fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.bucket.put_object(format!("/{}", key), data).await?;
+
+        Ok(())
+    }
+
+    async fn put_file(&self, key: &str, path: &Path) -> Result<()> {
+        let mut file = File::open(path).await?;
+
+        self.bucket
+            .put_object_stream(&mut file, format!("/{}", key))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        Ok(self.bucket.get_object(format!("/{}", key)).await?.to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.bucket.delete_object(format!("/{}", key)).await?;
+
+        Ok(())
+    }
+
+    async fn size(&self, key: &str) -> Result<u64> {
+        let (head, _) = self.bucket.head_object(format!("/{}", key)).await?;
+
+        Ok(head
+            .content_length
+            .ok_or(anyhow!("Missing content length for object: '{}'", key))? as u64)
+    }
+
+    async fn stream(&self, key: &str, range: Option<(u64, u64)>) -> Result<ByteStream> {
+        let path = format!("/{}", key);
+
+        let command = match range {
+            Some((start, end)) => Command::GetObjectRange {
+                start,
+                end: Some(end),
+            },
+
+            None => Command::GetObject,
+        };
+
+        let stream = HyperRequest::new(&self.bucket, &path, command)
+            .await?
+            .response_data_to_stream()
+            .await?;
+
+        Ok(Box::pin(stream.bytes.map_err(Into::into)))
+    }
+
+    async fn presign(
+        &self,
+        key: &str,
+        expiry_secs: u32,
+        file_name: &str,
+    ) -> Result<Option<String>> {
+        let queries = HashMap::from([(
+            "response-content-disposition".to_string(),
+            content_disposition(file_name),
+        )]);
+
+        Ok(Some(
+            self.bucket
+                .presign_get(format!("/{}", key), expiry_secs, Some(queries))
+                .await?,
+        ))
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{ByteStream, StorageBackend};
+use anyhow::anyhow;
+use app_core::{AppError, Result};
+use futures::TryStreamExt;
+use std::{
+    io::{ErrorKind, SeekFrom},
+    path::{Component, Path, PathBuf},
+};
+use tokio::{
+    fs::{self, File},
+    io::{AsyncReadExt, AsyncSeekExt},
+};
+use tokio_util::io::ReaderStream;
+
+/// A storage backend using a directory on the local filesystem.
+#[derive(Debug, Clone)]
+pub struct FsStorage {
+    root: PathBuf,
+}
+
+impl FsStorage {
+    /// Create a new filesystem storage backend rooted at a directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Get the path for a key, making sure it can't escape the root directory.
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        let key = Path::new(key);
+
+        if key.components().any(|v| !matches!(v, Component::Normal(_))) {
+            return Err(anyhow!("Invalid storage key: '{}'", key.display()).into());
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+/// Map a missing file to [`AppError::NotFound`].
+fn not_found(err: std::io::Error) -> AppError {
+    match err.kind() {
+        ErrorKind::NotFound => AppError::NotFound,
+        _ => err.into(),
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for FsStorage {
+    async fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        let path = self.path(key)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        fs::write(path, data).await?;
+
+        Ok(())
+    }
+
+    async fn put_file(&self, key: &str, from: &Path) -> Result<()> {
+        let path = self.path(key)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        fs::copy(from, path).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        fs::read(self.path(key)?).await.map_err(not_found)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.path(key)?).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn size(&self, key: &str) -> Result<u64> {
+        Ok(fs::metadata(self.path(key)?)
+            .await
+            .map_err(not_found)?
+            .len())
+    }
+
+    async fn stream(&self, key: &str, range: Option<(u64, u64)>) -> Result<ByteStream> {
+        let mut file = File::open(self.path(key)?).await.map_err(not_found)?;
+
+        let Some((start, end)) = range else {
+            return Ok(Box::pin(ReaderStream::new(file).map_err(Into::into)));
+        };
+
+        file.seek(SeekFrom::Start(start)).await?;
+
+        Ok(Box::pin(
+            ReaderStream::new(file.take(end - start + 1)).map_err(Into::into),
+        ))
+    }
+
+    async fn presign(
+        &self,
+        _key: &str,
+        _expiry_secs: u32,
+        _file_name: &str,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_cannot_escape_the_root() {
+        let storage = FsStorage::new("/data");
+
+        assert_eq!(storage.path("a/b").unwrap(), Path::new("/data/a/b"));
+        assert!(storage.path("../x").is_err());
+        assert!(storage.path("/abs").is_err());
+        assert!(storage.path("a/../../b").is_err());
+        assert!(storage.path("./a").is_err());
+    }
+
+    #[tokio::test]
+    async fn files_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FsStorage::new(dir.path());
+
+        storage.put("a/file", b"0123456789").await.unwrap();
+
+        assert_eq!(storage.get("a/file").await.unwrap(), b"0123456789");
+        assert_eq!(storage.size("a/file").await.unwrap(), 10);
+
+        storage.delete("a/file").await.unwrap();
+        storage.delete("a/file").await.unwrap();
+
+        assert!(matches!(
+            storage.get("a/file").await,
+            Err(AppError::NotFound)
+        ));
+        assert!(matches!(
+            storage.size("a/file").await,
+            Err(AppError::NotFound)
+        ));
+    }
+
+    async fn read(storage: &FsStorage, range: Option<(u64, u64)>) -> Vec<u8> {
+        storage
+            .stream("file", range)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .concat()
+    }
+
+    #[tokio::test]
+    async fn streams_return_the_requested_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FsStorage::new(dir.path());
+
+        storage.put("file", b"0123456789").await.unwrap();
+
+        assert_eq!(read(&storage, None).await, b"0123456789");
+        assert_eq!(read(&storage, Some((2, 5))).await, b"2345");
+        assert_eq!(read(&storage, Some((9, 9))).await, b"9");
+    }
+}
@@ -0,0 +1,9 @@
+mod backend;
+mod bucket;
+mod fs;
+mod service;
+
+pub use backend::*;
+pub use bucket::*;
+pub use fs::*;
+pub use service::*;
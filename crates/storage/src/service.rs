@@ -0,0 +1,24 @@
+use crate::{FsStorage, S3Storage, StorageBackend};
+use app_config::{StorageBackendKind, StorageConfig};
+use app_core::Result;
+use std::{path::PathBuf, sync::Arc};
+
+/// Open the storage backend for packages.
+pub fn packages_storage(config: &StorageConfig) -> Result<Arc<dyn StorageBackend>> {
+    Ok(match config.backend {
+        StorageBackendKind::S3 => Arc::new(S3Storage::new(config.packages()?)),
+        StorageBackendKind::Fs => Arc::new(FsStorage::new(
+            PathBuf::from(&config.fs_path).join(&config.packages_bucket),
+        )),
+    })
+}
+
+/// Open the storage backend for gallery images.
+pub fn gallery_storage(config: &StorageConfig) -> Result<Arc<dyn StorageBackend>> {
+    Ok(match config.backend {
+        StorageBackendKind::S3 => Arc::new(S3Storage::new(config.gallery()?)),
+        StorageBackendKind::Fs => Arc::new(FsStorage::new(
+            PathBuf::from(&config.fs_path).join(&config.gallery_bucket),
+        )),
+    })
+}